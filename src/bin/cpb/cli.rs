@@ -2,10 +2,18 @@ use clap::{crate_version, AppSettings, Parser};
 use common::color::{parse_color, COLOR_CHOICES};
 use common::help::highlight_static;
 use common::run::Options;
-use common::transfer::TransferOptions;
+use common::transfer::{
+    parse_summary_format, Reflink, SummaryFormat, TransferMode, TransferOptions, SUMMARY_CHOICES,
+};
 use indoc::indoc;
 use termcolor::ColorChoice;
 
+const REFLINK_MODES: &[&str] = &[AUTO, ALWAYS, NEVER];
+
+const AUTO: &str = "auto";
+const ALWAYS: &str = "always";
+const NEVER: &str = "never";
+
 #[derive(Debug, Parser)]
 #[clap(
     name = "cbp",
@@ -47,10 +55,103 @@ pub struct Cli {
     #[clap(short = 'z', long)]
     pub read_nul: bool,
 
+    /// Decode escape sequences in paths
+    #[clap(
+        long,
+        long_about = highlight_static(indoc!{"
+            Decode escape sequences in paths
+
+            Each `\\\\` is decoded as a backslash and each `\\xNN` as a byte with the hexadecimal value `NN`.
+            Use it to process output of `rew --invalid-utf8=escape`, which can represent paths with invalid UTF-8 encoding this way.
+        "}),
+    )]
+    pub unescape: bool,
+
+    /// When to clone file data instead of copying it
+    #[clap(
+        long,
+        value_name = "when",
+        default_value = AUTO,
+        possible_values = REFLINK_MODES,
+        parse(try_from_str = parse_reflink),
+        long_about = highlight_static(indoc!{"
+            When to clone file data instead of copying it
+
+            A reflink (copy-on-write clone) shares data of source and destination file until one of them is modified.
+            It is created instantly and takes no extra space, but it requires both files to be on the same file system which supports it (e.g., Btrfs or XFS).
+
+            - `auto` - Clone file data where possible, copy it otherwise.
+            - `always` - Clone file data, fail where it is not possible. Only supported on Linux.
+            - `never` - Always copy file data.
+        "}),
+    )]
+    pub reflink: Reflink,
+
     /// Continue processing after an error, fail at end
     #[clap(short = 'F', long)]
     pub fail_at_end: bool,
 
+    /// Reorder instructions to resolve chained and cyclic transfers
+    #[clap(
+        short = 'r',
+        long,
+        long_about = highlight_static(indoc!{"
+            Reorder instructions to resolve chained and cyclic transfers
+
+            All instructions are read before any transfer is done.
+            Transfers are then reordered, so that none of them overwrites source path of another one (e.g., `b -> c` is done before `a -> b`).
+            Cycles (e.g., `a -> b` and `b -> a`) are resolved by moving one of the source paths to a temporary path first.
+            The path is moved back from its temporary path when a later transfer fails.
+        "}),
+    )]
+    pub reorder: bool,
+
+    /// Only check instructions against file system, do not transfer anything
+    #[clap(
+        short = 'c',
+        long,
+        conflicts_with = "reorder",
+        long_about = highlight_static(indoc!{"
+            Only check instructions against file system, do not transfer anything
+
+            Each instruction is printed with one of the following statuses, which reflect what `cpb` would actually do:
+
+            - `applicable` - Source path exists, destination path does not or has the same type (existing file is overwritten, existing directory is merged).
+            - `already applied` - Source and destination path point to the same file or directory.
+            - `conflict` - Destination path has incompatible type (file versus directory).
+            - `source missing` - Source path does not exist.
+            - `case collision` - Destination path differs only in letter case from another destination path.
+
+            The status list is followed by a summary.
+            Use `--summary=json` to get statuses and the summary as a single JSON object instead.
+            Exit code is `5` when there is a conflict, a missing source path or a case collision.
+        "}),
+    )]
+    pub check: bool,
+
+    /// Print summary at the end (format defaults to `text`)
+    #[clap(
+        long,
+        value_name = "format",
+        possible_values = SUMMARY_CHOICES,
+        parse(try_from_str = parse_summary_format),
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "text",
+        long_about = highlight_static(indoc!{"
+            Print summary at the end (format defaults to `text`)
+
+            Summary contains number of processed, skipped (source and destination are the same) and failed instructions, number of transferred bytes and elapsed time.
+            First few error messages are included as well.
+
+            - `text` - Human readable summary.
+            - `json` - Single JSON object, suitable for automation.
+
+            With `-c, --check` flag, `json` format prints status of each instruction and number of instructions per status instead.
+        "}),
+    )]
+    pub summary: Option<SummaryFormat>,
+
     /// Explain what is being done
     #[clap(short = 'v', long)]
     pub verbose: bool,
@@ -68,11 +169,23 @@ pub struct Cli {
     #[clap(short = 'h', long)]
     pub help: bool,
 
+    /// Print exit code reference
+    #[clap(long)]
+    pub help_exit_codes: bool,
+
     /// Print version information
     #[clap(long)]
     pub version: bool,
 }
 
+impl Cli {
+    pub fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Copy {
+            reflink: self.reflink,
+        }
+    }
+}
+
 impl Options for Cli {
     fn color(&self) -> Option<ColorChoice> {
         self.color
@@ -91,6 +204,31 @@ impl TransferOptions for Cli {
     fn fail_at_end(&self) -> bool {
         self.fail_at_end
     }
+
+    fn check(&self) -> bool {
+        self.check
+    }
+
+    fn summary(&self) -> Option<SummaryFormat> {
+        self.summary
+    }
+
+    fn unescape(&self) -> bool {
+        self.unescape
+    }
+
+    fn reorder(&self) -> bool {
+        self.reorder
+    }
+}
+
+fn parse_reflink(string: &str) -> Result<Reflink, &'static str> {
+    match string {
+        AUTO => Ok(Reflink::Auto),
+        ALWAYS => Ok(Reflink::Always),
+        NEVER => Ok(Reflink::Never),
+        _ => Err("invalid value"),
+    }
 }
 
 #[cfg(test)]
@@ -117,13 +255,47 @@ mod tests {
         assert_eq!(run(args).verbose(), result);
     }
 
+    #[test_case(&[],             false ; "off")]
+    #[test_case(&["--unescape"], true  ; "on")]
+    fn unescape(args: &[&str], result: bool) {
+        assert_eq!(run(args).unescape(), result);
+    }
+
+    #[test_case(&[],                   TransferMode::Copy { reflink: Reflink::Auto }   ; "default")]
+    #[test_case(&["--reflink=auto"],   TransferMode::Copy { reflink: Reflink::Auto }   ; "reflink auto")]
+    #[test_case(&["--reflink=always"], TransferMode::Copy { reflink: Reflink::Always } ; "reflink always")]
+    #[test_case(&["--reflink=never"],  TransferMode::Copy { reflink: Reflink::Never }  ; "reflink never")]
+    fn transfer_mode(args: &[&str], result: TransferMode) {
+        assert_eq!(run(args).transfer_mode(), result);
+    }
+
     #[test_case(&[],                false ; "off")]
     #[test_case(&["--fail-at-end"], true  ; "on")]
     fn fail_at_end(args: &[&str], result: bool) {
         assert_eq!(run(args).fail_at_end(), result);
     }
 
+    #[test_case(&[],            false ; "off")]
+    #[test_case(&["--reorder"], true  ; "on")]
+    fn reorder(args: &[&str], result: bool) {
+        assert_eq!(run(args).reorder(), result);
+    }
+
+    #[test_case(&[],          false ; "off")]
+    #[test_case(&["--check"], true  ; "on")]
+    fn check(args: &[&str], result: bool) {
+        assert_eq!(run(args).check(), result);
+    }
+
+    #[test_case(&[],                 None                      ; "off")]
+    #[test_case(&["--summary"],      Some(SummaryFormat::Text) ; "default")]
+    #[test_case(&["--summary=text"], Some(SummaryFormat::Text) ; "text")]
+    #[test_case(&["--summary=json"], Some(SummaryFormat::Json) ; "json")]
+    fn summary(args: &[&str], result: Option<SummaryFormat>) {
+        assert_eq!(run(args).summary(), result);
+    }
+
     fn run(args: &[&str]) -> Cli {
-        Cli::try_parse_from(&[&["cpb"], args].concat()).unwrap()
+        Cli::try_parse_from([&["cpb"], args].concat()).unwrap()
     }
 }
@@ -1,6 +1,7 @@
 use cli::Cli;
-use common::run::{exec_run, Io, Result};
-use common::transfer::{run_transfer, TransferMode};
+use common::help::{highlight, EXIT_CODES};
+use common::run::{exec_run, Io, Result, EXIT_CODE_OK};
+use common::transfer::run_transfer;
 
 mod cli;
 
@@ -9,5 +10,10 @@ fn main() {
 }
 
 fn run(cli: &Cli, io: &Io) -> Result {
-    run_transfer(cli, io, TransferMode::Copy)
+    if cli.help_exit_codes {
+        highlight(&mut io.stdout(), EXIT_CODES)?;
+        return Ok(EXIT_CODE_OK);
+    }
+
+    run_transfer(cli, io, cli.transfer_mode())
 }
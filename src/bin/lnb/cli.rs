@@ -0,0 +1,297 @@
+use clap::{crate_version, AppSettings, Parser};
+use common::color::{parse_color, COLOR_CHOICES};
+use common::help::highlight_static;
+use common::run::Options;
+use common::transfer::{
+    parse_summary_format, SummaryFormat, TransferMode, TransferOptions, SUMMARY_CHOICES,
+};
+use indoc::indoc;
+use termcolor::ColorChoice;
+
+const LINK_TYPES: &[&str] = &[SYMLINK, HARDLINK];
+
+const SYMLINK: &str = "symlink";
+const HARDLINK: &str = "hardlink";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkType {
+    Symlink,
+    Hardlink,
+}
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "lnb",
+    version = crate_version!(),
+    long_about = highlight_static(indoc!{"
+        Bulk link files and directories
+
+        `lnb` reads instructions from standard input in the following format:
+
+            <src_path_1
+            >dst_path_1
+            <src_path_2
+            >dst_path_2
+            ...
+            <src_path_N
+            >dst_path_N
+
+        Such input can be generated using accompanying `rew` utility and its `-d, --diff` flag:
+
+            $> find -name '*.txt' | rew -d 'links/{f}' | lnb # Link each *.txt file into links directory
+
+        A link is created at each destination path, pointing to the source path.
+
+        Source path must exist. Using non-existent source path will result in error.
+
+        Destination path may exist. Existing destination file or link (including a link to a directory) will be replaced. Existing destination directory will result in error.
+
+        Missing parent directories in destination path will be created as needed.
+
+        Nothing will be done if source and destination paths point to the same file or directory.
+    "}),
+    after_help = highlight_static("Use `-h` for short descriptions and `--help` for more details."),
+    setting(AppSettings::DeriveDisplayOrder),
+    setting(AppSettings::DontCollapseArgsInUsage),
+)]
+/// Bulk link files and directories
+pub struct Cli {
+    /// Read instructions terminated by NUL character, not newline
+    #[clap(short = 'z', long)]
+    pub read_nul: bool,
+
+    /// Type of created links
+    #[clap(
+        short = 'm',
+        long,
+        value_name = "type",
+        default_value = SYMLINK,
+        possible_values = LINK_TYPES,
+        parse(try_from_str = parse_link_type),
+        long_about = highlight_static(indoc!{"
+            Type of created links
+
+            - `symlink` - Symbolic link, source path may be a file or a directory.
+            - `hardlink` - Hard link, source path must be a file on the same device as destination path.
+        "}),
+    )]
+    pub mode: LinkType,
+
+    /// Make symbolic link targets relative to link location
+    #[clap(
+        short = 'R',
+        long,
+        long_about = highlight_static(indoc!{"
+            Make symbolic link targets relative to link location
+
+            By default, symbolic links point to absolute source paths.
+            Ignored for hard links.
+        "}),
+    )]
+    pub relative: bool,
+
+    /// Decode escape sequences in paths
+    #[clap(
+        long,
+        long_about = highlight_static(indoc!{"
+            Decode escape sequences in paths
+
+            Each `\\\\` is decoded as a backslash and each `\\xNN` as a byte with the hexadecimal value `NN`.
+            Use it to process output of `rew --invalid-utf8=escape`, which can represent paths with invalid UTF-8 encoding this way.
+        "}),
+    )]
+    pub unescape: bool,
+
+    /// Continue processing after an error, fail at end
+    #[clap(short = 'F', long)]
+    pub fail_at_end: bool,
+
+    /// Only check instructions against file system, do not transfer anything
+    #[clap(
+        short = 'c',
+        long,
+        long_about = highlight_static(indoc!{"
+            Only check instructions against file system, do not transfer anything
+
+            Each instruction is printed with one of the following statuses, which reflect what `lnb` would actually do:
+
+            - `applicable` - Source path exists, destination path does not or is a file or link (which gets replaced).
+            - `already applied` - Destination path already points to the source path.
+            - `conflict` - Destination path is a directory, or a hard link to a directory was requested.
+            - `source missing` - Source path does not exist.
+            - `case collision` - Destination path differs only in letter case from another destination path.
+
+            The status list is followed by a summary.
+            Use `--summary=json` to get statuses and the summary as a single JSON object instead.
+            Exit code is `5` when there is a conflict, a missing source path or a case collision.
+        "}),
+    )]
+    pub check: bool,
+
+    /// Print summary at the end (format defaults to `text`)
+    #[clap(
+        long,
+        value_name = "format",
+        possible_values = SUMMARY_CHOICES,
+        parse(try_from_str = parse_summary_format),
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "text",
+        long_about = highlight_static(indoc!{"
+            Print summary at the end (format defaults to `text`)
+
+            Summary contains number of processed, skipped (source and destination are the same) and failed instructions, number of transferred bytes and elapsed time.
+            First few error messages are included as well.
+
+            - `text` - Human readable summary.
+            - `json` - Single JSON object, suitable for automation.
+
+            With `-c, --check` flag, `json` format prints status of each instruction and number of instructions per status instead.
+        "}),
+    )]
+    pub summary: Option<SummaryFormat>,
+
+    /// Explain what is being done
+    #[clap(short = 'v', long)]
+    pub verbose: bool,
+
+    /// When to use colors
+    #[clap(
+        long,
+        value_name = "when",
+        possible_values = COLOR_CHOICES,
+        parse(try_from_str = parse_color),
+    )]
+    pub color: Option<ColorChoice>,
+
+    /// Print help information
+    #[clap(short = 'h', long)]
+    pub help: bool,
+
+    /// Print exit code reference
+    #[clap(long)]
+    pub help_exit_codes: bool,
+
+    /// Print version information
+    #[clap(long)]
+    pub version: bool,
+}
+
+impl Cli {
+    pub fn transfer_mode(&self) -> TransferMode {
+        match self.mode {
+            LinkType::Symlink => TransferMode::Symlink {
+                relative: self.relative,
+            },
+            LinkType::Hardlink => TransferMode::Hardlink,
+        }
+    }
+}
+
+impl Options for Cli {
+    fn color(&self) -> Option<ColorChoice> {
+        self.color
+    }
+}
+
+impl TransferOptions for Cli {
+    fn read_nul(&self) -> bool {
+        self.read_nul
+    }
+
+    fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    fn fail_at_end(&self) -> bool {
+        self.fail_at_end
+    }
+
+    fn check(&self) -> bool {
+        self.check
+    }
+
+    fn summary(&self) -> Option<SummaryFormat> {
+        self.summary
+    }
+
+    fn unescape(&self) -> bool {
+        self.unescape
+    }
+
+    fn reorder(&self) -> bool {
+        false // Links do not remove their source paths, so there is nothing to reorder
+    }
+}
+
+fn parse_link_type(string: &str) -> Result<LinkType, &'static str> {
+    match string {
+        SYMLINK => Ok(LinkType::Symlink),
+        HARDLINK => Ok(LinkType::Hardlink),
+        _ => Err("invalid value"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(&[],                 None                      ; "default")]
+    #[test_case(&["--color=always"], Some(ColorChoice::Always) ; "always")]
+    fn color(args: &[&str], result: Option<ColorChoice>) {
+        assert_eq!(run(args).color(), result);
+    }
+
+    #[test_case(&[],             false ; "off")]
+    #[test_case(&["--read-nul"], true  ; "on")]
+    fn read_nul(args: &[&str], result: bool) {
+        assert_eq!(run(args).read_nul(), result);
+    }
+
+    #[test_case(&[],            false ; "off")]
+    #[test_case(&["--verbose"], true  ; "on")]
+    fn verbose(args: &[&str], result: bool) {
+        assert_eq!(run(args).verbose(), result);
+    }
+
+    #[test_case(&[],             false ; "off")]
+    #[test_case(&["--unescape"], true  ; "on")]
+    fn unescape(args: &[&str], result: bool) {
+        assert_eq!(run(args).unescape(), result);
+    }
+
+    #[test_case(&[],                false ; "off")]
+    #[test_case(&["--fail-at-end"], true  ; "on")]
+    fn fail_at_end(args: &[&str], result: bool) {
+        assert_eq!(run(args).fail_at_end(), result);
+    }
+
+    #[test_case(&[],                               TransferMode::Symlink { relative: false } ; "default")]
+    #[test_case(&["--relative"],                   TransferMode::Symlink { relative: true }  ; "relative")]
+    #[test_case(&["--mode=symlink"],               TransferMode::Symlink { relative: false } ; "symlink")]
+    #[test_case(&["--mode=hardlink"],              TransferMode::Hardlink                    ; "hardlink")]
+    #[test_case(&["--mode=hardlink", "--relative"], TransferMode::Hardlink                    ; "hardlink relative")]
+    fn transfer_mode(args: &[&str], result: TransferMode) {
+        assert_eq!(run(args).transfer_mode(), result);
+    }
+
+    #[test_case(&[],          false ; "off")]
+    #[test_case(&["--check"], true  ; "on")]
+    fn check(args: &[&str], result: bool) {
+        assert_eq!(run(args).check(), result);
+    }
+
+    #[test_case(&[],                 None                      ; "off")]
+    #[test_case(&["--summary"],      Some(SummaryFormat::Text) ; "default")]
+    #[test_case(&["--summary=text"], Some(SummaryFormat::Text) ; "text")]
+    #[test_case(&["--summary=json"], Some(SummaryFormat::Json) ; "json")]
+    fn summary(args: &[&str], result: Option<SummaryFormat>) {
+        assert_eq!(run(args).summary(), result);
+    }
+
+    fn run(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["lnb"], args].concat()).unwrap()
+    }
+}
@@ -0,0 +1,19 @@
+use cli::Cli;
+use common::help::{highlight, EXIT_CODES};
+use common::run::{exec_run, Io, Result, EXIT_CODE_OK};
+use common::transfer::run_transfer;
+
+mod cli;
+
+fn main() {
+    exec_run(run);
+}
+
+fn run(cli: &Cli, io: &Io) -> Result {
+    if cli.help_exit_codes {
+        highlight(&mut io.stdout(), EXIT_CODES)?;
+        return Ok(EXIT_CODE_OK);
+    }
+
+    run_transfer(cli, io, cli.transfer_mode())
+}
@@ -1,5 +1,6 @@
 use cli::Cli;
-use common::run::{exec_run, Io, Result};
+use common::help::{highlight, EXIT_CODES};
+use common::run::{exec_run, Io, Result, EXIT_CODE_OK};
 use common::transfer::{run_transfer, TransferMode};
 
 mod cli;
@@ -9,5 +10,10 @@ fn main() {
 }
 
 fn run(cli: &Cli, io: &Io) -> Result {
+    if cli.help_exit_codes {
+        highlight(&mut io.stdout(), EXIT_CODES)?;
+        return Ok(EXIT_CODE_OK);
+    }
+
     run_transfer(cli, io, TransferMode::Move)
 }
@@ -3,12 +3,14 @@ use std::path::PathBuf;
 use clap::{crate_name, crate_version, AppSettings, Parser};
 use common::color::{parse_color, COLOR_CHOICES};
 use common::help::highlight_static;
+use common::input::{parse_invalid_utf8, InvalidUtf8, INVALID_UTF8_CHOICES};
 use common::run::Options;
 use indoc::indoc;
 use regex::Regex;
 use termcolor::ColorChoice;
 
 use crate::counter;
+use crate::output::{parse_shell, Shell, SCRIPT_CHOICES};
 use crate::pattern::eval;
 
 const INPUT_HEADING: Option<&str> = Some("INPUT OPTIONS");
@@ -79,6 +81,24 @@ pub struct Cli {
     #[clap(short = 'l', long, help_heading = INPUT_HEADING)]
     pub read_end: bool,
 
+    /// How to handle input values with invalid UTF-8 encoding
+    #[clap(
+        long,
+        value_name = "policy",
+        possible_values = INVALID_UTF8_CHOICES,
+        parse(try_from_str = parse_invalid_utf8),
+        help_heading = INPUT_HEADING,
+        long_about = highlight_static(indoc!{"
+            How to handle input values with invalid UTF-8 encoding
+
+            - `error` - Fail with an error (default).
+            - `lossy` - Replace invalid sequences with `U+FFFD` replacement character.
+            - `escape` - Replace each invalid byte with its `\\xNN` escape sequence and each backslash with `\\\\` (decoded by `mvb --unescape`).
+            - `skip` - Ignore such values.
+        "}),
+    )]
+    pub invalid_utf8: Option<InvalidUtf8>,
+
     /// Do not read values from standard input
     ///
     /// By default, when no values are passed as arguments, they are read from standard input instead.
@@ -123,11 +143,45 @@ pub struct Cli {
     #[clap(short = 'L', long, help_heading = OUTPUT_HEADING)]
     pub no_print_end: bool,
 
+    /// Write results to a file instead of standard output
+    #[clap(
+        short = 'o',
+        long,
+        value_name = "path",
+        help_heading = OUTPUT_HEADING,
+        long_about = highlight_static(indoc!{"
+            Write results to a file instead of standard output
+
+            Results are written to a temporary file which replaces the target file only when `rew` succeeds.
+            The previous file content is left intact on failure.
+            Unlike shell redirection, this allows `rew` to safely read its input from the same file.
+            Permissions of the target file are kept. When the target is a symbolic link, the file it points to is replaced.
+        "}),
+    )]
+    pub output: Option<PathBuf>,
+
+    /// Append results to the output file instead of replacing its content
+    #[clap(short = 'a', long, requires = "output", help_heading = OUTPUT_HEADING)]
+    pub append: bool,
+
+    /// Fail when a printed value contains the output terminator
+    #[clap(
+        long,
+        help_heading = OUTPUT_HEADING,
+        long_about = highlight_static(indoc!{"
+            Fail when a printed value contains the output terminator
+
+            Such value would be indistinguishable from multiple values for a program reading the output (e.g. `mvb` or `cpb`).
+            Applies to standard and diff output mode, where both input and output values are checked.
+        "}),
+    )]
+    pub strict_terminator: bool,
+
     /// Enable diff output mode
     #[clap(
         short = 'd',
         long,
-        conflicts_with_all = &["pretty", "json-lines"],
+        conflicts_with_all = &["pretty", "json-lines", "script"],
         help_heading = OUTPUT_HEADING,
         long_about = highlight_static(indoc!{"
             Enable diff output mode
@@ -153,7 +207,7 @@ pub struct Cli {
     #[clap(
         short = 'p',
         long,
-        conflicts_with_all = &["diff", "json-lines"],
+        conflicts_with_all = &["diff", "json-lines", "script"],
         help_heading = OUTPUT_HEADING,
         long_about = highlight_static(indoc!{"
             Enable pretty output mode
@@ -174,7 +228,7 @@ pub struct Cli {
     #[clap(
     short = 'j',
     long,
-    conflicts_with_all = &["pretty", "diff"],
+    conflicts_with_all = &["pretty", "diff", "script"],
     help_heading = OUTPUT_HEADING,
     long_about = highlight_static(indoc!{r#"
             Enable JSON lines mode
@@ -191,6 +245,43 @@ pub struct Cli {
     )]
     pub json_lines: bool,
 
+    /// Enable script output mode (shell defaults to `sh`)
+    #[clap(
+        long,
+        value_name = "shell",
+        possible_values = SCRIPT_CHOICES,
+        parse(try_from_str = parse_shell),
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "sh",
+        conflicts_with_all = &["pretty", "diff", "json-lines"],
+        help_heading = OUTPUT_HEADING,
+        long_about = highlight_static(indoc!{"
+            Enable script output mode (shell defaults to `sh`)
+
+            Ignores `--print*` flags/options.
+            Ignores `--no-print-end` flag.
+            Prints transformations as a shell script which moves each input path to its output path:
+
+                #!/bin/sh
+                set -e
+                mv -- 'input_value_1' 'output_value_1'
+                mv -- 'input_value_2' 'output_value_2'
+                ...
+                mv -- 'input_value_N' 'output_value_N'
+
+            - `sh` - POSIX shell script, which stops at the first failed command.
+            - `pwsh` - PowerShell script, which stops at the first failed command.
+
+            Values are quoted, so the script is safe to run with any characters in paths.
+            Missing parent directories of output paths are created before each move (the same way as `mvb` does).
+            Values which were not changed by the pattern are omitted.
+            Empty input produces a script with only the header.
+            Use it to review a rename plan or to apply it where `mvb` is not available.
+        "}),
+    )]
+    pub script: Option<Shell>,
+
     /// When to use colors
     #[clap(
         long,
@@ -255,6 +346,21 @@ pub struct Cli {
     #[clap(short = 'F', long, help_heading = PROCESSING_HEADING)]
     pub fail_at_end: bool,
 
+    /// Seed for random number and UUID generation
+    #[clap(
+        long,
+        value_name = "number",
+        env = "REW_SEED",
+        help_heading = PROCESSING_HEADING,
+        long_about = highlight_static(indoc!{"
+            Seed for random number and UUID generation
+
+            Makes output of `u` and `U` filters reproducible across runs.
+            The same seed and input always produce the same results.
+        "}),
+    )]
+    pub seed: Option<u64>,
+
     /// Print explanation of a given pattern
     #[clap(long, requires = "pattern", help_heading = PATTERN_HEADING)]
     pub explain: bool,
@@ -279,7 +385,7 @@ pub struct Cli {
 
     /// Default field separator (string)
     ///
-    /// This value will be used as a separator for field filter.
+    /// This value will be used as a separator for field and reverse fields filters.
     #[clap(
         short = 's',
         long,
@@ -291,7 +397,7 @@ pub struct Cli {
 
     /// Default field separator (regular expression)
     ///
-    /// This value will be used as a separator for field filter.
+    /// This value will be used as a separator for field and reverse fields filters.
     #[clap(
         short = 'S',
         long,
@@ -317,6 +423,10 @@ pub struct Cli {
     #[clap(long, help_heading = HELP_HEADING)]
     pub help_filters: bool,
 
+    /// Print exit code reference
+    #[clap(long, help_heading = HELP_HEADING)]
+    pub help_exit_codes: bool,
+
     /// Print version information
     #[clap(long, help_heading = HELP_HEADING)]
     pub version: bool,
@@ -351,7 +461,7 @@ mod tests {
     }
 
     fn run(args: &[&str]) -> Cli {
-        Cli::try_parse_from(&[&["rew"], args].concat()).unwrap()
+        Cli::try_parse_from([&["rew"], args].concat()).unwrap()
     }
 
     mod parse_single_byte_char {
@@ -1,7 +1,7 @@
 use std::io::{BufRead, Result};
 use std::slice::Iter;
 
-use common::input::{Splitter, Terminator};
+use common::input::{InvalidUtf8, Splitter, Terminator};
 
 pub enum Values<'a, A: AsRef<str>, I: BufRead> {
     Args { iter: Iter<'a, A> },
@@ -15,9 +15,9 @@ impl<'a, A: AsRef<str>, I: BufRead> Values<'a, A, I> {
         }
     }
 
-    pub fn from_stdin(stdin: I, terminator: Terminator) -> Self {
+    pub fn from_stdin(stdin: I, terminator: Terminator, invalid_utf8: InvalidUtf8) -> Self {
         Values::Stdin {
-            splitter: Splitter::new(stdin, terminator),
+            splitter: Splitter::new(stdin, terminator, invalid_utf8),
         }
     }
 
@@ -54,6 +54,10 @@ mod tests {
     }
 
     fn stdin<'a>() -> Values<'a, &'a str, &'a [u8]> {
-        Values::from_stdin(&b"a\nb"[..], Terminator::Newline { required: false })
+        Values::from_stdin(
+            &b"a\nb"[..],
+            Terminator::Newline { required: false },
+            InvalidUtf8::Error,
+        )
     }
 }
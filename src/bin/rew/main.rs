@@ -1,16 +1,24 @@
+use std::cell::RefCell;
 use std::env;
 use std::io::Write;
 
 use ::regex::Regex;
-use common::help::highlight;
-use common::input::Terminator;
-use common::run::{exec_run, Io, Result, EXIT_CODE_OK};
+use common::file::AtomicFile;
+use common::help::{highlight, EXIT_CODES};
+use common::input::{is_stdin_tty, write_stdin_hint, InvalidUtf8, Terminator};
+use common::run::{
+    exec_run, Io, Result, EXIT_CODE_EVAL_ERROR, EXIT_CODE_OK, EXIT_CODE_PARSE_ERROR,
+};
+
+use rand::SeedableRng;
+use termcolor::{NoColor, WriteColor};
 
 use crate::cli::Cli;
 use crate::output::write_pattern_error;
+use crate::pattern::eval::Random;
 use crate::pattern::parse::Separator;
 use crate::pattern::regex::RegexHolder;
-use crate::pattern::{eval, help, parse, Pattern};
+use crate::pattern::{eval, help, parse, path, Pattern};
 
 mod cli;
 mod counter;
@@ -19,9 +27,6 @@ mod output;
 mod pattern;
 mod regex;
 
-const EXIT_CODE_PARSE_ERROR: i32 = 3;
-const EXIT_CODE_EVAL_ERROR: i32 = 4;
-
 fn main() {
     exec_run(run);
 }
@@ -37,48 +42,12 @@ fn run(cli: &Cli, io: &Io) -> Result {
         return Ok(EXIT_CODE_OK);
     }
 
-    let mut input_values = if cli.values.is_empty() && !cli.no_stdin {
-        let required = cli.read_end;
-        let terminator = if let Some(value) = cli.read {
-            Terminator::Byte { value, required }
-        } else if cli.read_nul {
-            Terminator::Byte { value: 0, required }
-        } else if cli.read_raw {
-            Terminator::None
-        } else {
-            Terminator::Newline { required }
-        };
-        input::Values::from_stdin(io.stdin(), terminator)
-    } else {
-        input::Values::from_args(cli.values.as_slice())
-    };
-
-    let output_mode = if cli.pretty {
-        output::Mode::Pretty
-    } else if cli.diff {
-        output::Mode::Diff
-    } else if cli.json_lines {
-        output::Mode::JsonLines
-    } else if cli.no_print_end {
-        output::Mode::StandardNoEnd
-    } else {
-        output::Mode::Standard
-    };
-
-    let output_terminator = if let Some(terminator) = &cli.print {
-        terminator
-    } else if cli.print_raw {
-        ""
-    } else if cli.print_nul {
-        "\0"
-    } else {
-        "\n"
-    };
-
-    let mut output_values = output::Values::new(io.stdout(), output_mode, output_terminator);
-    let mut exit_code = EXIT_CODE_OK;
+    if cli.help_exit_codes {
+        highlight(&mut io.stdout(), EXIT_CODES)?;
+        return Ok(EXIT_CODE_OK);
+    }
 
-    if let Some(raw_pattern) = cli.pattern.as_ref() {
+    let pattern = if let Some(raw_pattern) = cli.pattern.as_ref() {
         let separator = if let Some(separator) = &cli.separator {
             Separator::String(separator.clone())
         } else if let Some(separator) = &cli.separator_regex {
@@ -119,6 +88,87 @@ fn run(cli: &Cli, io: &Io) -> Result {
             return Ok(EXIT_CODE_OK);
         }
 
+        Some(pattern)
+    } else {
+        None
+    };
+
+    if let Some(path) = &cli.output {
+        let mut file = AtomicFile::create(path, cli.append)?;
+        let exit_code = process(cli, io, pattern.as_ref(), NoColor::new(&mut file))?;
+
+        if exit_code == EXIT_CODE_OK {
+            file.commit()?;
+        }
+
+        Ok(exit_code)
+    } else {
+        process(cli, io, pattern.as_ref(), io.stdout())
+    }
+}
+
+fn process<O: Write + WriteColor>(
+    cli: &Cli,
+    io: &Io,
+    pattern: Option<&Pattern>,
+    output: O,
+) -> Result {
+    let mut input_values = if cli.values.is_empty() && !cli.no_stdin {
+        let required = cli.read_end;
+        let terminator = if let Some(value) = cli.read {
+            Terminator::Byte { value, required }
+        } else if cli.read_nul {
+            Terminator::Byte { value: 0, required }
+        } else if cli.read_raw {
+            Terminator::None
+        } else {
+            Terminator::Newline { required }
+        };
+        let invalid_utf8 = cli.invalid_utf8.unwrap_or(InvalidUtf8::Error);
+        if is_stdin_tty() {
+            write_stdin_hint(
+                &mut io.stderr(),
+                "Reading input values from standard input, they can be also passed as arguments.",
+            )?;
+        }
+        input::Values::from_stdin(io.stdin(), terminator, invalid_utf8)
+    } else {
+        input::Values::from_args(cli.values.as_slice())
+    };
+
+    let output_mode = if cli.pretty {
+        output::Mode::Pretty
+    } else if cli.diff {
+        output::Mode::Diff
+    } else if cli.json_lines {
+        output::Mode::JsonLines
+    } else if let Some(shell) = cli.script {
+        output::Mode::Script(shell)
+    } else if cli.no_print_end {
+        output::Mode::StandardNoEnd
+    } else {
+        output::Mode::Standard
+    };
+
+    let output_terminator = if let Some(terminator) = &cli.print {
+        terminator
+    } else if cli.print_raw {
+        ""
+    } else if cli.print_nul {
+        "\0"
+    } else {
+        "\n"
+    };
+
+    let mut output_values = output::Values::new(
+        output,
+        output_mode,
+        output_terminator,
+        cli.strict_terminator,
+    );
+    let mut exit_code = EXIT_CODE_OK;
+
+    if let (Some(raw_pattern), Some(pattern)) = (cli.pattern.as_ref(), pattern) {
         let global_counter_used = pattern.uses_global_counter();
         let local_counter_used = pattern.uses_local_counter();
         let regex_capture_used = pattern.uses_regex_capture();
@@ -147,12 +197,20 @@ fn run(cli: &Cli, io: &Io) -> Result {
             env::current_dir()?
         };
 
+        let home_dir = path::home_dir();
+
         let expression_quotes = match cli.quote {
             0 => None,
             1 => Some('\''),
             _ => Some('"'),
         };
 
+        let random = RefCell::new(if let Some(seed) = cli.seed {
+            Random::seed_from_u64(seed)
+        } else {
+            Random::from_entropy()
+        });
+
         while let Some(input_value) = input_values.next()? {
             let global_counter = if global_counter_used {
                 global_counter_generator.next()
@@ -174,10 +232,12 @@ fn run(cli: &Cli, io: &Io) -> Result {
 
             let context = eval::Context {
                 working_dir: &working_dir,
+                home_dir: home_dir.as_deref(),
                 global_counter,
                 local_counter,
                 regex_captures,
                 expression_quotes,
+                random: &random,
             };
 
             let output_value = match pattern.eval(input_value, &context) {
@@ -201,6 +261,6 @@ fn run(cli: &Cli, io: &Io) -> Result {
         }
     };
 
-    io.stdout().flush()?; // output::Values may not do flush if there is no last terminator.
+    output_values.flush()?; // output::Values may not do flush if there is no last terminator.
     Ok(exit_code)
 }
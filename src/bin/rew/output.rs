@@ -1,5 +1,5 @@
 use std::error::Error;
-use std::io::{Result, Write};
+use std::io::{self, ErrorKind, Result, Write};
 use std::ops::Range;
 
 use common::color::{spec_bold_color, spec_color};
@@ -8,6 +8,26 @@ use common::symbols::{DIFF_IN, DIFF_OUT};
 use termcolor::{Color, WriteColor};
 
 use crate::pattern::error::GetErrorRange;
+use crate::pattern::escape::escape_str;
+
+pub const SCRIPT_CHOICES: &[&str] = &[SH, PWSH];
+
+const SH: &str = "sh";
+const PWSH: &str = "pwsh";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    Sh,
+    Pwsh,
+}
+
+pub fn parse_shell(string: &str) -> std::result::Result<Shell, &'static str> {
+    match string {
+        SH => Ok(Shell::Sh),
+        PWSH => Ok(Shell::Pwsh),
+        _ => Err("invalid value"),
+    }
+}
 
 pub enum Mode {
     Standard,
@@ -15,22 +35,25 @@ pub enum Mode {
     Diff,
     Pretty,
     JsonLines,
+    Script(Shell),
 }
 
 pub struct Values<O: Write + WriteColor> {
     output: O,
     mode: Mode,
     terminator: String,
+    strict_terminator: bool,
     first_result: bool,
     flush_needed: bool,
 }
 
 impl<O: Write + WriteColor> Values<O> {
-    pub fn new(output: O, mode: Mode, terminator: &str) -> Self {
+    pub fn new(output: O, mode: Mode, terminator: &str, strict_terminator: bool) -> Self {
         Self {
             output,
             mode,
             terminator: terminator.into(),
+            strict_terminator,
             first_result: true,
             flush_needed: !terminator.ends_with('\n'),
         }
@@ -39,10 +62,12 @@ impl<O: Write + WriteColor> Values<O> {
     pub fn write(&mut self, input_value: &str, output_value: &str) -> Result<()> {
         match self.mode {
             Mode::Standard => {
+                self.check_terminator(output_value)?;
                 write!(self.output, "{}{}", output_value, self.terminator)?;
                 self.flush_if_needed()
             }
             Mode::StandardNoEnd => {
+                self.check_terminator(output_value)?;
                 if self.first_result {
                     self.first_result = false;
                 } else {
@@ -52,6 +77,8 @@ impl<O: Write + WriteColor> Values<O> {
                 write!(self.output, "{}", output_value)
             }
             Mode::Diff => {
+                self.check_terminator(input_value)?;
+                self.check_terminator(output_value)?;
                 write!(
                     self.output,
                     "{}{}{}{}{}{}",
@@ -74,6 +101,80 @@ impl<O: Write + WriteColor> Values<O> {
                     input_value, output_value
                 )
             }
+            Mode::Script(shell) => {
+                self.write_script_header(shell)?;
+                if input_value == output_value {
+                    return Ok(()); // Moving path to itself would fail
+                }
+                match shell {
+                    Shell::Sh => {
+                        if output_value.contains('/') {
+                            writeln!(
+                                self.output,
+                                "mkdir -p -- \"$(dirname -- {})\"",
+                                quote_sh(output_value)
+                            )?;
+                        }
+                        writeln!(
+                            self.output,
+                            "mv -- {} {}",
+                            quote_sh(input_value),
+                            quote_sh(output_value)
+                        )
+                    }
+                    Shell::Pwsh => {
+                        if output_value.contains(&['/', '\\'][..]) {
+                            writeln!(
+                                self.output,
+                                "New-Item -Force -ItemType Directory -Path (Split-Path -Parent -LiteralPath {}) | Out-Null",
+                                quote_pwsh(output_value)
+                            )?;
+                        }
+                        writeln!(
+                            self.output,
+                            "Move-Item -LiteralPath {} -Destination {}",
+                            quote_pwsh(input_value),
+                            quote_pwsh(output_value)
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        if let Mode::Script(shell) = self.mode {
+            self.write_script_header(shell)?; // Even an empty script should have it
+        }
+        self.output.flush()
+    }
+
+    fn write_script_header(&mut self, shell: Shell) -> Result<()> {
+        if !self.first_result {
+            return Ok(());
+        }
+        self.first_result = false;
+        match shell {
+            Shell::Sh => writeln!(self.output, "#!/bin/sh\nset -e"),
+            Shell::Pwsh => writeln!(self.output, "$ErrorActionPreference = 'Stop'"),
+        }
+    }
+
+    fn check_terminator(&self, value: &str) -> Result<()> {
+        if self.strict_terminator
+            && !self.terminator.is_empty()
+            && value.contains(self.terminator.as_str())
+        {
+            Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Value '{}' contains output terminator '{}'",
+                    escape_str(value),
+                    escape_str(&self.terminator)
+                ),
+            ))
+        } else {
+            Ok(())
         }
     }
 
@@ -86,6 +187,27 @@ impl<O: Write + WriteColor> Values<O> {
     }
 }
 
+// Nothing is special inside single quotes, except the quote itself
+fn quote_sh(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// PowerShell treats typographic single quotes the same way as the ASCII one
+const PWSH_QUOTES: &[char] = &['\'', '\u{2018}', '\u{2019}', '\u{201A}', '\u{201B}'];
+
+fn quote_pwsh(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('\'');
+    for char in value.chars() {
+        if PWSH_QUOTES.contains(&char) {
+            result.push(char);
+        }
+        result.push(char);
+    }
+    result.push('\'');
+    result
+}
+
 pub fn write_pattern_error<O: Write + WriteColor, E: Error + GetErrorRange>(
     output: &mut O,
     error: &E,
@@ -122,7 +244,7 @@ pub fn highlight_range<O: Write + WriteColor>(
 
 #[cfg(test)]
 mod tests {
-    use common::testing::{ColoredOuput, OutputChunk};
+    use common::testing::{unpack_io_error, ColoredOuput, OutputChunk};
     use indoc::indoc;
     use test_case::test_case;
 
@@ -143,18 +265,119 @@ mod tests {
                                                {"in":"a","out":"b"}
                                                {"in":"c","out":"d"}
                                            "#})                      ; "json lines")]
+    #[test_case(Mode::Script(Shell::Sh), "x", plain(indoc! {"
+                                               #!/bin/sh
+                                               set -e
+                                               mv -- 'a' 'b'
+                                               mv -- 'c' 'd'
+                                           "})                       ; "script sh")]
+    #[test_case(Mode::Script(Shell::Pwsh), "x", plain(indoc! {"
+                                               $ErrorActionPreference = 'Stop'
+                                               Move-Item -LiteralPath 'a' -Destination 'b'
+                                               Move-Item -LiteralPath 'c' -Destination 'd'
+                                           "})                       ; "script pwsh")]
     fn values_write(mode: Mode, terminator: &str, chunks: Vec<OutputChunk>) {
         let mut output = ColoredOuput::new();
-        let mut values = Values::new(&mut output, mode, terminator);
+        let mut values = Values::new(&mut output, mode, terminator, false);
         values.write("a", "b").unwrap();
         values.write("c", "d").unwrap();
         assert_eq!(output.chunks(), &chunks);
     }
 
+    #[test_case(Mode::Standard,      "\n", "a", "b",    plain("b\n")              ; "standard ok")]
+    #[test_case(Mode::StandardNoEnd, "\n", "a", "b",    plain("b")                ; "standard no end ok")]
+    #[test_case(Mode::Diff,          "\n", "a", "b",    plain("<a\n>b\n")         ; "diff ok")]
+    #[test_case(Mode::Standard,      "",   "a", "b\nc", plain("b\nc")             ; "standard no terminator")]
+    #[test_case(Mode::Pretty,        "x",  "a", "bxc",  pretty_single("a", "bxc") ; "pretty ignored")]
+    fn values_write_strict_ok(
+        mode: Mode,
+        terminator: &str,
+        input_value: &str,
+        output_value: &str,
+        chunks: Vec<OutputChunk>,
+    ) {
+        let mut output = ColoredOuput::new();
+        let mut values = Values::new(&mut output, mode, terminator, true);
+        values.write(input_value, output_value).unwrap();
+        assert_eq!(output.chunks(), &chunks);
+    }
+
+    #[test_case(Mode::Standard,      "\n", "a",    "b\nc", "Value 'b\\nc' contains output terminator '\\n'" ; "standard output")]
+    #[test_case(Mode::StandardNoEnd, "\0", "a",    "b\0c", "Value 'b\\0c' contains output terminator '\\0'" ; "standard no end output")]
+    #[test_case(Mode::Diff,          "\n", "a\nb", "c",    "Value 'a\\nb' contains output terminator '\\n'" ; "diff input")]
+    #[test_case(Mode::Diff,          ";;", "a",    "b;;c", "Value 'b;;c' contains output terminator ';;'"   ; "diff output")]
+    fn values_write_strict_err(
+        mode: Mode,
+        terminator: &str,
+        input_value: &str,
+        output_value: &str,
+        error: &str,
+    ) {
+        let mut output = ColoredOuput::new();
+        let mut values = Values::new(&mut output, mode, terminator, true);
+        assert_eq!(
+            values
+                .write(input_value, output_value)
+                .map_err(unpack_io_error),
+            Err((ErrorKind::InvalidData, error.into()))
+        );
+        assert_eq!(output.chunks(), &[]);
+    }
+
+    #[test_case(Shell::Sh,   "a",        "a",          ""                                                                              ; "sh same")]
+    #[test_case(Shell::Sh,   "a'b",      "c d",        "mv -- 'a'\\''b' 'c d'\n"                                                        ; "sh quoted")]
+    #[test_case(Shell::Sh,   "-a",       "$b*",        "mv -- '-a' '$b*'\n"                                                              ; "sh special")]
+    #[test_case(Shell::Sh,   "a",        "b/c",        "mkdir -p -- \"$(dirname -- 'b/c')\"\nmv -- 'a' 'b/c'\n"                           ; "sh parent dir")]
+    #[test_case(Shell::Pwsh, "a",        "a",          ""                                                                              ; "pwsh same")]
+    #[test_case(Shell::Pwsh, "a'b",      "c d",        "Move-Item -LiteralPath 'a''b' -Destination 'c d'\n"                             ; "pwsh quoted")]
+    #[test_case(Shell::Pwsh, "Don’t",    "‘a‚b‛",      "Move-Item -LiteralPath 'Don’’t' -Destination '‘‘a‚‚b‛‛'\n"                        ; "pwsh typographic quotes")]
+    #[test_case(Shell::Pwsh, "a",        "b\\c",       "New-Item -Force -ItemType Directory -Path (Split-Path -Parent -LiteralPath 'b\\c') | Out-Null\nMove-Item -LiteralPath 'a' -Destination 'b\\c'\n" ; "pwsh parent dir")]
+    fn values_write_script(shell: Shell, input_value: &str, output_value: &str, result: &str) {
+        let mut output = ColoredOuput::new();
+        let mut values = Values::new(&mut output, Mode::Script(shell), "\n", false);
+        values.write(input_value, output_value).unwrap();
+        values.flush().unwrap();
+        assert_eq!(
+            output.chunks(),
+            &plain(&format!("{}{}", script_header(shell), result))
+        );
+    }
+
+    #[test_case(Shell::Sh   ; "sh")]
+    #[test_case(Shell::Pwsh ; "pwsh")]
+    fn values_flush_empty_script(shell: Shell) {
+        let mut output = ColoredOuput::new();
+        let mut values = Values::new(&mut output, Mode::Script(shell), "\n", false);
+        values.flush().unwrap();
+        assert_eq!(output.chunks(), &plain(script_header(shell)));
+    }
+
+    fn script_header(shell: Shell) -> &'static str {
+        match shell {
+            Shell::Sh => "#!/bin/sh\nset -e\n",
+            Shell::Pwsh => "$ErrorActionPreference = 'Stop'\n",
+        }
+    }
+
+    #[test_case(SH,   Ok(Shell::Sh)         ; "sh")]
+    #[test_case(PWSH, Ok(Shell::Pwsh)       ; "pwsh")]
+    #[test_case("x",  Err("invalid value")  ; "invalid")]
+    fn parse_shell(value: &str, result: std::result::Result<Shell, &str>) {
+        assert_eq!(super::parse_shell(value), result);
+    }
+
     pub fn plain(value: &str) -> Vec<OutputChunk> {
         vec![OutputChunk::plain(value)]
     }
 
+    fn pretty_single(input_value: &str, output_value: &str) -> Vec<OutputChunk> {
+        vec![
+            OutputChunk::color(Color::Blue, input_value),
+            OutputChunk::plain(" -> "),
+            OutputChunk::color(Color::Green, &format!("{}\n", output_value)),
+        ]
+    }
+
     fn pretty() -> Vec<OutputChunk> {
         vec![
             OutputChunk::color(Color::Blue, "a"),
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use crate::pattern::char::Char;
+use crate::pattern::integer::parse_integer;
+use crate::pattern::parse::Result;
+use crate::pattern::reader::Reader;
+
+const FIRST: char = '^';
+const LAST: char = '$';
+
+#[derive(Debug, PartialEq)]
+pub enum DigitRuns {
+    All,
+    First,
+    Last,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DigitPadding {
+    pub width: usize,
+    pub runs: DigitRuns,
+}
+
+impl DigitPadding {
+    pub fn parse(reader: &mut Reader<Char>) -> Result<Self> {
+        let width = parse_integer(reader)?;
+        let runs = if reader.read_expected(FIRST) {
+            DigitRuns::First
+        } else if reader.read_expected(LAST) {
+            DigitRuns::Last
+        } else {
+            DigitRuns::All
+        };
+        Ok(Self { width, runs })
+    }
+
+    pub fn apply(&self, value: &str) -> String {
+        let runs = find_digit_runs(value);
+        let selected = match self.runs {
+            DigitRuns::All => &runs[..],
+            DigitRuns::First => &runs[..runs.len().min(1)],
+            DigitRuns::Last => &runs[runs.len().saturating_sub(1)..],
+        };
+
+        let mut result = String::with_capacity(value.len());
+        let mut last_end = 0;
+
+        for &(start, end) in selected {
+            result.push_str(&value[last_end..start]);
+            result.push_str(&"0".repeat(self.width.saturating_sub(end - start)));
+            result.push_str(&value[start..end]);
+            last_end = end;
+        }
+
+        result.push_str(&value[last_end..]);
+        result
+    }
+}
+
+// Byte ranges of ASCII digit runs
+fn find_digit_runs(value: &str) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+
+    for (index, char) in value.char_indices() {
+        match (char.is_ascii_digit(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(run_start)) => {
+                runs.push((run_start, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(run_start) = start {
+        runs.push((run_start, value.len()));
+    }
+
+    runs
+}
+
+impl fmt::Display for DigitPadding {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let runs = match self.runs {
+            DigitRuns::All => "all numbers",
+            DigitRuns::First => "first number",
+            DigitRuns::Last => "last number",
+        };
+        write!(formatter, "{} to {} digits", runs, self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    mod parse {
+        use test_case::test_case;
+
+        use super::*;
+        use crate::pattern::error::ErrorRange;
+        use crate::pattern::parse::{Error, ErrorKind};
+
+        #[test_case("",   0..0, ErrorKind::ExpectedNumber ; "empty")]
+        #[test_case("^",  0..1, ErrorKind::ExpectedNumber ; "no width")]
+        fn err(input: &str, range: ErrorRange, kind: ErrorKind) {
+            assert_eq!(
+                DigitPadding::parse(&mut Reader::from(input)),
+                Err(Error { kind, range })
+            );
+        }
+
+        #[test_case("3",  3, DigitRuns::All   ; "all")]
+        #[test_case("3^", 3, DigitRuns::First ; "first")]
+        #[test_case("3$", 3, DigitRuns::Last  ; "last")]
+        fn ok(input: &str, width: usize, runs: DigitRuns) {
+            assert_eq!(
+                DigitPadding::parse(&mut Reader::from(input)),
+                Ok(DigitPadding { width, runs })
+            );
+        }
+    }
+
+    #[test_case("",           3, DigitRuns::All,   ""                ; "empty")]
+    #[test_case("abc",        3, DigitRuns::All,   "abc"             ; "no number")]
+    #[test_case("1",          3, DigitRuns::All,   "001"             ; "number only")]
+    #[test_case("a1b22c333",  3, DigitRuns::All,   "a001b022c333"    ; "all")]
+    #[test_case("a1b22c333",  3, DigitRuns::First, "a001b22c333"     ; "first")]
+    #[test_case("a1b22c333",  3, DigitRuns::Last,  "a1b22c333"       ; "last already wide")]
+    #[test_case("a1b22c",     3, DigitRuns::Last,  "a1b022c"         ; "last")]
+    #[test_case("file12345",  3, DigitRuns::All,   "file12345"       ; "longer than width")]
+    #[test_case("č1ř2",       2, DigitRuns::All,   "č01ř02"          ; "non-ascii")]
+    fn apply(input: &str, width: usize, runs: DigitRuns, output: &str) {
+        assert_eq!(DigitPadding { width, runs }.apply(input), output);
+    }
+
+    #[test_case(3, DigitRuns::All,   "all numbers to 3 digits"  ; "all")]
+    #[test_case(3, DigitRuns::First, "first number to 3 digits" ; "first")]
+    #[test_case(3, DigitRuns::Last,  "last number to 3 digits"  ; "last")]
+    fn display(width: usize, runs: DigitRuns, result: &str) {
+        assert_eq!(DigitPadding { width, runs }.to_string(), result);
+    }
+}
@@ -1,18 +1,26 @@
+use std::cell::RefCell;
 use std::path::Path;
 use std::{error, fmt, result};
 
+use rand_chacha::ChaCha8Rng;
+
 use crate::pattern::error::{ErrorRange, GetErrorRange};
 use crate::pattern::filter::Filter;
 use crate::pattern::utils::AnyString;
 
 pub type Counter = u32;
 
+// Named algorithm, so seeded output does not change with rand upgrades
+pub type Random = ChaCha8Rng;
+
 pub struct Context<'a> {
     pub working_dir: &'a Path,
+    pub home_dir: Option<&'a Path>,
     pub global_counter: Counter,
     pub local_counter: Counter,
     pub regex_captures: Option<regex::Captures<'a>>,
     pub expression_quotes: Option<char>,
+    pub random: &'a RefCell<Random>,
 }
 
 impl<'a> Context<'a> {
@@ -24,16 +32,26 @@ impl<'a> Context<'a> {
     }
 
     #[cfg(test)]
-    pub fn fixture() -> Self {
+    pub fn random_fixture() -> RefCell<Random> {
+        RefCell::new(rand::SeedableRng::seed_from_u64(0))
+    }
+
+    #[cfg(test)]
+    pub fn fixture(random: &'a RefCell<Random>) -> Self {
         Context {
             #[cfg(unix)]
             working_dir: Path::new("/work"),
             #[cfg(windows)]
             working_dir: Path::new("C:\\work"),
+            #[cfg(unix)]
+            home_dir: Some(Path::new("/home/user")),
+            #[cfg(windows)]
+            home_dir: Some(Path::new("C:\\Users\\user")),
             local_counter: 1,
             global_counter: 2,
             regex_captures: regex::Regex::new("(.).(.)").unwrap().captures("abc"),
             expression_quotes: None,
+            random,
         }
     }
 }
@@ -71,6 +89,7 @@ impl<'a> fmt::Display for Error<'a> {
 pub enum ErrorKind {
     InputNotUtf8,
     CanonicalizationFailed(AnyString),
+    ExpansionFailed(String),
 }
 
 impl fmt::Display for ErrorKind {
@@ -80,6 +99,9 @@ impl fmt::Display for ErrorKind {
             Self::CanonicalizationFailed(reason) => {
                 write!(formatter, "Path canonicalization failed: {}", reason)
             }
+            Self::ExpansionFailed(reason) => {
+                write!(formatter, "Path expansion failed: {}", reason)
+            }
         }
     }
 }
@@ -98,7 +120,8 @@ mod tests {
         #[test_case(0 ; "position 0")]
         #[test_case(1 ; "position 1")]
         fn none(position: usize) {
-            let mut context = Context::fixture();
+            let random = Context::random_fixture();
+            let mut context = Context::fixture(&random);
             context.regex_captures = None;
             assert_eq!(context.regex_capture(position), "");
         }
@@ -108,7 +131,8 @@ mod tests {
         #[test_case(2, "c"   ; "position 2")]
         #[test_case(3, ""    ; "position 3")]
         fn some(number: usize, result: &str) {
-            assert_eq!(Context::fixture().regex_capture(number), result);
+            let random = Context::random_fixture();
+            assert_eq!(Context::fixture(&random).regex_capture(number), result);
         }
     }
 
@@ -146,6 +170,7 @@ mod tests {
 
     #[test_case(ErrorKind::InputNotUtf8,                         "Input does not have UTF-8 encoding" ; "input not utf-8")]
     #[test_case(ErrorKind::CanonicalizationFailed("abc".into()), "Path canonicalization failed: abc"  ; "canonicalization failed")]
+    #[test_case(ErrorKind::ExpansionFailed("abc".into()),        "Path expansion failed: abc"         ; "expansion failed")]
     fn error_kind_display(kind: ErrorKind, result: &str) {
         assert_eq!(kind.to_string(), result);
     }
@@ -1,5 +1,6 @@
 use std::convert::TryInto;
 use std::fmt;
+use std::ops::Range;
 
 use crate::pattern::char::Char;
 use crate::pattern::index::parse_index;
@@ -73,6 +74,42 @@ impl Field {
     }
 }
 
+// Separators stay in place, only fields between them are reversed
+pub fn reverse_fields(value: &str, separator: &Separator) -> String {
+    let separator_ranges: Vec<Range<usize>> = match separator {
+        Separator::String(separator) if separator.is_empty() => Vec::new(),
+        Separator::String(separator) => value
+            .match_indices(separator.as_str())
+            .map(|(start, separator)| start..(start + separator.len()))
+            .collect(),
+        Separator::Regex(separator) => separator
+            .find_iter(value)
+            .filter(|separator| !separator.range().is_empty())
+            .map(|separator| separator.range())
+            .collect(),
+    };
+
+    let mut fields = Vec::with_capacity(separator_ranges.len() + 1);
+    let mut field_start = 0;
+
+    for range in &separator_ranges {
+        fields.push(&value[field_start..range.start]);
+        field_start = range.end;
+    }
+    fields.push(&value[field_start..]);
+
+    let mut result = String::with_capacity(value.len());
+
+    for (index, field) in fields.iter().rev().enumerate() {
+        result.push_str(field);
+        if let Some(range) = separator_ranges.get(index) {
+            result.push_str(&value[range.clone()]);
+        }
+    }
+
+    result
+}
+
 impl fmt::Display for Field {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -174,6 +211,20 @@ mod tests {
                 "field #2 ('_' separator)"
             );
         }
+
+        #[test_case("",         "",   ""         ; "empty")]
+        #[test_case("a b c",    "",   "a b c"    ; "empty separator")]
+        #[test_case("abc",      " ",  "abc"      ; "single field")]
+        #[test_case("a b c",    " ",  "c b a"    ; "fields")]
+        #[test_case("ab, c, d", ", ", "d, c, ab" ; "long separator")]
+        #[test_case(" a b",     " ",  "b a "     ; "first empty")]
+        #[test_case("a b ",     " ",  " b a"     ; "last empty")]
+        fn reverse_fields(input: &str, separator: &str, output: &str) {
+            assert_eq!(
+                super::reverse_fields(input, &Separator::String(separator.into())),
+                output
+            );
+        }
     }
 
     mod regex {
@@ -244,5 +295,17 @@ mod tests {
                 "field #2 (regular expression '[0-9]+' separator)"
             );
         }
+
+        #[test_case("",         "\\s+",   ""         ; "empty")]
+        #[test_case("abc",      "\\s+",   "abc"      ; "single field")]
+        #[test_case("a\t\tb c", "\\s+",   "c\t\tb a" ; "fields")]
+        #[test_case("a1b22c",   "[0-9]+", "c1b22a"   ; "separators kept in place")]
+        #[test_case("abc",      "x*",     "abc"      ; "empty matches")]
+        fn reverse_fields(input: &str, separator: &str, output: &str) {
+            assert_eq!(
+                super::reverse_fields(input, &Separator::Regex(separator.into())),
+                output
+            );
+        }
     }
 }
@@ -1,12 +1,14 @@
-use std::fmt;
+use std::{env, fmt};
 
 use unidecode::unidecode;
 
 use crate::pattern::char::{AsChar, Char};
-use crate::pattern::field::Field;
+use crate::pattern::digits::DigitPadding;
+use crate::pattern::field::{reverse_fields, Field};
 use crate::pattern::integer::parse_integer;
 use crate::pattern::number::NumberRange;
 use crate::pattern::padding::Padding;
+use crate::pattern::parse::Separator;
 use crate::pattern::reader::Reader;
 use crate::pattern::regex::RegexMatcher;
 use crate::pattern::repeat::Repetition;
@@ -28,12 +30,14 @@ pub enum Filter {
     RemoveLastName,
     FileName,
     LastName,
-    BaseName,
-    RemoveExtension,
-    Extension,
-    ExtensionWithDot,
+    BaseName(usize),
+    RemoveExtension(usize),
+    Extension(usize),
+    ExtensionWithDot(usize),
     EnsureTrailDirSeparator,
     RemoveTrailDirSeparator,
+    ExpandHomeAndVars,
+    ContractHomeDir,
     Substring(CharIndexRange),
     SubstringRev(CharIndexRange),
     GetField(Field),
@@ -52,8 +56,11 @@ pub enum Filter {
     ToUppercase,
     ToAscii,
     RemoveNonAscii,
+    ReverseChars,
+    ReverseFields(Separator),
     LeftPad(Padding),
     RightPad(Padding),
+    PadDigits(DigitPadding),
     Repeat(Repetition),
     LocalCounter,
     GlobalCounter,
@@ -76,12 +83,14 @@ impl Filter {
                 'D' => Ok(Self::RemoveLastName),
                 'f' => Ok(Self::FileName),
                 'F' => Ok(Self::LastName),
-                'b' => Ok(Self::BaseName),
-                'B' => Ok(Self::RemoveExtension),
-                'e' => Ok(Self::Extension),
-                'E' => Ok(Self::ExtensionWithDot),
+                'b' => Ok(Self::BaseName(parse_extension_levels(reader)?)),
+                'B' => Ok(Self::RemoveExtension(parse_extension_levels(reader)?)),
+                'e' => Ok(Self::Extension(parse_extension_levels(reader)?)),
+                'E' => Ok(Self::ExtensionWithDot(parse_extension_levels(reader)?)),
                 'z' => Ok(Self::EnsureTrailDirSeparator),
                 'Z' => Ok(Self::RemoveTrailDirSeparator),
+                'x' => Ok(Self::ExpandHomeAndVars),
+                'X' => Ok(Self::ContractHomeDir),
                 '#' => {
                     if reader.read_expected(REVERSE_INDEX) {
                         Ok(Self::SubstringRev(CharIndexRange::parse(reader)?))
@@ -116,8 +125,11 @@ impl Filter {
                 '^' => Ok(Self::ToUppercase),
                 'i' => Ok(Self::ToAscii),
                 'I' => Ok(Self::RemoveNonAscii),
+                'y' => Ok(Self::ReverseChars),
+                'Y' => Ok(Self::ReverseFields(config.separator.clone())),
                 '<' => Ok(Self::LeftPad(Padding::parse(reader, '<')?)),
                 '>' => Ok(Self::RightPad(Padding::parse(reader, '>')?)),
+                'n' => Ok(Self::PadDigits(DigitPadding::parse(reader)?)),
                 '*' => Ok(Self::Repeat(Repetition::parse(reader)?)),
                 'c' => Ok(Self::LocalCounter),
                 'C' => Ok(Self::GlobalCounter),
@@ -147,12 +159,19 @@ impl Filter {
             Self::RemoveLastName => path::remove_last_name(value),
             Self::FileName => path::get_file_name(&value),
             Self::LastName => path::get_last_name(&value),
-            Self::BaseName => path::get_base_name(&value),
-            Self::RemoveExtension => path::remove_extension(value),
-            Self::Extension => path::get_extension(&value),
-            Self::ExtensionWithDot => path::get_extension_with_dot(&value),
+            Self::BaseName(levels) => path::get_base_name(&value, *levels),
+            Self::RemoveExtension(levels) => path::remove_extension(value, *levels),
+            Self::Extension(levels) => path::get_extension(&value, *levels),
+            Self::ExtensionWithDot(levels) => path::get_extension_with_dot(&value, *levels),
             Self::EnsureTrailDirSeparator => Ok(path::ensure_trailing_dir_separator(value)),
             Self::RemoveTrailDirSeparator => Ok(path::remove_trailing_dir_separator(value)),
+            Self::ExpandHomeAndVars => path::expand_home_and_vars(
+                &value,
+                context.home_dir,
+                |name| env::var(name).ok(),
+                path::user_home_dir,
+            ),
+            Self::ContractHomeDir => path::contract_home_dir(value, context.home_dir),
             Self::Substring(range) => Ok(range.substr(value)),
             Self::SubstringRev(range) => Ok(range.substr_rev(value)),
             Self::GetField(field) => Ok(field.get(&value).to_string()),
@@ -174,13 +193,18 @@ impl Filter {
                 value.retain(|ch| ch.is_ascii());
                 Ok(value)
             }
+            Self::ReverseChars => Ok(value.chars().rev().collect()),
+            Self::ReverseFields(separator) => Ok(reverse_fields(&value, separator)),
             Self::LeftPad(padding) => Ok(padding.apply_left(value)),
             Self::RightPad(padding) => Ok(padding.apply_right(value)),
+            Self::PadDigits(padding) => Ok(padding.apply(&value)),
             Self::Repeat(repetition) => Ok(repetition.expand(&value)),
             Self::LocalCounter => Ok(context.local_counter.to_string()),
             Self::GlobalCounter => Ok(context.global_counter.to_string()),
-            Self::RandomNumber(range) => Ok(range.random().to_string()),
-            Self::RandomUuid => Ok(random_uuid()),
+            Self::RandomNumber(range) => {
+                Ok(range.random(&mut *context.random.borrow_mut()).to_string())
+            }
+            Self::RandomUuid => Ok(random_uuid(&mut *context.random.borrow_mut())),
         }
     }
 }
@@ -197,16 +221,22 @@ impl fmt::Display for Filter {
             Self::RemoveLastName => write!(formatter, "Remove last name"),
             Self::FileName => write!(formatter, "File name"),
             Self::LastName => write!(formatter, "Last name"),
-            Self::BaseName => write!(formatter, "Base name"),
-            Self::RemoveExtension => write!(formatter, "Remove extension"),
-            Self::Extension => write!(formatter, "Extension"),
-            Self::ExtensionWithDot => write!(formatter, "Extension with dot"),
+            Self::BaseName(1) => write!(formatter, "Base name"),
+            Self::BaseName(levels) => write!(formatter, "Base name without {} extensions", levels),
+            Self::RemoveExtension(1) => write!(formatter, "Remove extension"),
+            Self::RemoveExtension(levels) => write!(formatter, "Remove {} extensions", levels),
+            Self::Extension(1) => write!(formatter, "Extension"),
+            Self::Extension(levels) => write!(formatter, "{} extensions", levels),
+            Self::ExtensionWithDot(1) => write!(formatter, "Extension with dot"),
+            Self::ExtensionWithDot(levels) => write!(formatter, "{} extensions with dot", levels),
             Self::EnsureTrailDirSeparator => {
                 write!(formatter, "Ensure trailing directory separator")
             }
             Self::RemoveTrailDirSeparator => {
                 write!(formatter, "Remove trailing directory separator")
             }
+            Self::ExpandHomeAndVars => write!(formatter, "Expand home and variables"),
+            Self::ContractHomeDir => write!(formatter, "Contract home directory"),
             Self::Substring(range) => write!(formatter, "Substring from {}", range),
             Self::SubstringRev(range) => {
                 write!(formatter, "Substring from {} backward", range)
@@ -247,8 +277,13 @@ impl fmt::Display for Filter {
             Self::ToUppercase => write!(formatter, "To uppercase"),
             Self::ToAscii => write!(formatter, "To ASCII"),
             Self::RemoveNonAscii => write!(formatter, "Remove non-ASCII"),
+            Self::ReverseChars => write!(formatter, "Reverse characters"),
+            Self::ReverseFields(separator) => {
+                write!(formatter, "Reverse fields ({} separator)", separator)
+            }
             Self::LeftPad(padding) => write!(formatter, "Left pad with {}", padding),
             Self::RightPad(padding) => write!(formatter, "Right pad with {}", padding),
+            Self::PadDigits(padding) => write!(formatter, "Zero-pad {}", padding),
             Self::Repeat(repetition) => write!(formatter, "Repeat {}", repetition),
             Self::LocalCounter => write!(formatter, "Local counter"),
             Self::GlobalCounter => write!(formatter, "Global counter"),
@@ -258,6 +293,14 @@ impl fmt::Display for Filter {
     }
 }
 
+fn parse_extension_levels(reader: &mut Reader<Char>) -> parse::Result<usize> {
+    if let Some('0'..='9') = reader.peek_char() {
+        parse_integer(reader)
+    } else {
+        Ok(1)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     extern crate regex;
@@ -265,6 +308,7 @@ mod tests {
     use test_case::test_case;
 
     use super::Filter;
+    use crate::pattern::digits::{DigitPadding, DigitRuns};
     use crate::pattern::error::ErrorRange;
     use crate::pattern::field::Field;
     use crate::pattern::number::NumberRange;
@@ -330,12 +374,18 @@ mod tests {
         #[test_case("D",            F::RemoveLastName                         ; "remove last name")]
         #[test_case("f",            F::FileName                               ; "file name")]
         #[test_case("F",            F::LastName                               ; "last name")]
-        #[test_case("b",            F::BaseName                               ; "base name")]
-        #[test_case("B",            F::RemoveExtension                        ; "remove extension")]
-        #[test_case("e",            F::Extension                              ; "extension")]
-        #[test_case("E",            F::ExtensionWithDot                       ; "extension with dot")]
+        #[test_case("b",            F::BaseName(1)                            ; "base name")]
+        #[test_case("b2",           F::BaseName(2)                            ; "base name levels")]
+        #[test_case("B",            F::RemoveExtension(1)                     ; "remove extension")]
+        #[test_case("B2",           F::RemoveExtension(2)                     ; "remove extension levels")]
+        #[test_case("e",            F::Extension(1)                           ; "extension")]
+        #[test_case("e2",           F::Extension(2)                           ; "extension levels")]
+        #[test_case("E",            F::ExtensionWithDot(1)                    ; "extension with dot")]
+        #[test_case("E2",           F::ExtensionWithDot(2)                    ; "extension with dot levels")]
         #[test_case("z",            F::EnsureTrailDirSeparator                ; "ensure trail dir separator")]
         #[test_case("Z",            F::RemoveTrailDirSeparator                ; "remove trail dir separator")]
+        #[test_case("x",            F::ExpandHomeAndVars                      ; "expand home and vars")]
+        #[test_case("X",            F::ContractHomeDir                        ; "contract home dir")]
         #[test_case("#2",           F::Substring(index_range_at())            ; "substring at")]
         #[test_case("#2-",          F::Substring(index_range_from())          ; "substring from")]
         #[test_case("#2-3",         F::Substring(index_range_between())       ; "substring between")]
@@ -373,10 +423,13 @@ mod tests {
         #[test_case("^",            F::ToUppercase                            ; "to uppercase")]
         #[test_case("i",            F::ToAscii                                ; "to ascii")]
         #[test_case("I",            F::RemoveNonAscii                         ; "remove non-ascii")]
+        #[test_case("y",            F::ReverseChars                           ; "reverse chars")]
+        #[test_case("Y",            F::ReverseFields(separator_default())     ; "reverse fields")]
         #[test_case("<<abcd",       F::LeftPad(padding_fixed())               ; "left pad fixed")]
         #[test_case("<2:abc",       F::LeftPad(padding_repeated())            ; "left pad repeated")]
         #[test_case(">>abcd",       F::RightPad(padding_fixed())              ; "right pad fixed")]
         #[test_case(">2:abc",       F::RightPad(padding_repeated())           ; "right pad repeated")]
+        #[test_case("n3^",          F::PadDigits(digit_padding())             ; "pad digits")]
         #[test_case("*2",           F::Repeat(repetition_input())             ; "repetition input ")]
         #[test_case("*2:abc",       F::Repeat(repetition_value())             ; "repetition value ")]
         #[test_case("c",            F::LocalCounter                           ; "local counter")]
@@ -410,8 +463,13 @@ mod tests {
         use crate::pattern::uuid::assert_uuid;
 
         #[test_case("non-existent", F::CanonicalPath, ErrorKind::CanonicalizationFailed(AnyString::any()) ; "canonicalization failed")]
+        #[test_case("$REW_UNSET_VAR", F::ExpandHomeAndVars, ErrorKind::ExpansionFailed("Environment variable 'REW_UNSET_VAR' is not set".into()) ; "expansion failed")]
         fn err(input: &str, filter: Filter, kind: ErrorKind) {
-            assert_eq!(filter.eval(input.into(), &Context::fixture()), Err(kind))
+            let random = Context::random_fixture();
+            assert_eq!(
+                filter.eval(input.into(), &Context::fixture(&random)),
+                Err(kind)
+            )
         }
 
         #[cfg_attr(unix, test_case("",              F::WorkingDir,              "/work"            ; "working dir"))]
@@ -420,20 +478,28 @@ mod tests {
         #[cfg_attr(unix, test_case("/b/c.d",        F::RelativePath,            "../b/c.d"         ; "relative path"))]
         #[cfg_attr(unix, test_case("./Cargo.toml",  F::CanonicalPath,           "/work/Cargo.toml" ; "canonical path"))]
         #[cfg_attr(unix, test_case("a/b",           F::EnsureTrailDirSeparator, "a/b/"             ; "ensure trail dir separator"))]
+        #[cfg_attr(unix, test_case("~/a/b",         F::ExpandHomeAndVars,       "/home/user/a/b"   ; "expand home and vars"))]
+        #[cfg_attr(unix, test_case("/home/user/a",  F::ContractHomeDir,         "~/a"              ; "contract home dir"))]
         #[cfg_attr(windows, test_case("",                    F::WorkingDir,              "C:\\work"             ; "working dir"))]
         #[cfg_attr(windows, test_case("b\\c.d",              F::AbsolutePath,            "C:\\work\\b\\c.d"     ; "absolute path"))]
         #[cfg_attr(windows, test_case("C:\\b\\c.d",          F::RelativePath,            "..\\b\\c.d"           ; "relative path"))]
         #[cfg_attr(windows, test_case("a\\b\\..\\e\\.\\f\\", F::NormalizedPath,          "a\\e\\f"              ; "normalized path"))]
         #[cfg_attr(windows, test_case("./Cargo.toml",        F::CanonicalPath,           "C:\\work\\Cargo.toml" ; "canonical path"))]
         #[cfg_attr(windows, test_case("a\\b",                F::EnsureTrailDirSeparator, "a\\b\\"               ; "ensure trail dir separator"))]
+        #[cfg_attr(windows, test_case("~\\a\\b",             F::ExpandHomeAndVars,       "C:\\Users\\user\\a\\b" ; "expand home and vars"))]
+        #[cfg_attr(windows, test_case("C:\\Users\\user\\a",  F::ContractHomeDir,         "~\\a"                ; "contract home dir"))]
         #[test_case("a/b/c.d",       F::ParentDirectory,                        "a/b"      ; "parent directory")]
         #[test_case("a/b/c.d",       F::RemoveLastName,                         "a/b"      ; "remove last name")]
         #[test_case("a/b/c.d",       F::FileName,                               "c.d"      ; "file name")]
         #[test_case("a/b/c.d",       F::LastName,                               "c.d"      ; "last name")]
-        #[test_case("a/b/c.d",       F::BaseName,                               "c"        ; "base name")]
-        #[test_case("a/b/c.d",       F::RemoveExtension,                        "a/b/c"    ; "remove extension")]
-        #[test_case("a/b/c.d",       F::Extension,                              "d"        ; "extension")]
-        #[test_case("a/b/c.d",       F::ExtensionWithDot,                       ".d"       ; "extension with dot")]
+        #[test_case("a/b/c.d",       F::BaseName(1),                            "c"        ; "base name")]
+        #[test_case("a/b/c.d.e",     F::BaseName(2),                            "c"        ; "base name levels")]
+        #[test_case("a/b/c.d",       F::RemoveExtension(1),                     "a/b/c"    ; "remove extension")]
+        #[test_case("a/b/c.d.e",     F::RemoveExtension(2),                     "a/b/c"    ; "remove extension levels")]
+        #[test_case("a/b/c.d",       F::Extension(1),                           "d"        ; "extension")]
+        #[test_case("a/b/c.d.e",     F::Extension(2),                           "d.e"      ; "extension levels")]
+        #[test_case("a/b/c.d",       F::ExtensionWithDot(1),                    ".d"       ; "extension with dot")]
+        #[test_case("a/b/c.d.e",     F::ExtensionWithDot(2),                    ".d.e"     ; "extension with dot levels")]
         #[test_case("a/b/",          F::RemoveTrailDirSeparator,                "a/b"      ; "remove trail dir separator")]
         #[test_case("abcde",         F::Substring(index_range_at()),            "b"        ; "substring at")]
         #[test_case("abcde",         F::Substring(index_range_from()),          "bcde"     ; "substring from")]
@@ -470,10 +536,13 @@ mod tests {
         #[test_case("ábčdÁBČD",      F::ToUppercase,                            "ÁBČDÁBČD" ; "to uppercase")]
         #[test_case("ábčdÁBČD",      F::ToAscii,                                "abcdABCD" ; "to ascii")]
         #[test_case("ábčdÁBČD",      F::RemoveNonAscii,                         "bdBD"     ; "remove non-ascii")]
+        #[test_case("ábčd",          F::ReverseChars,                           "dčbá"     ; "reverse chars")]
+        #[test_case("a b\tc",        F::ReverseFields(separator_default()),     "c b\ta"   ; "reverse fields")]
         #[test_case("01",            F::LeftPad(padding_fixed()),               "ab01"     ; "left pad fixed")]
         #[test_case("01",            F::LeftPad(padding_repeated()),            "abca01"   ; "left pad repeated")]
         #[test_case("01",            F::RightPad(padding_fixed()),              "01cd"     ; "right pad fixed")]
         #[test_case("01",            F::RightPad(padding_repeated()),           "01cabc"   ; "right pad repeated")]
+        #[test_case("a1b2",          F::PadDigits(digit_padding()),             "a001b2"   ; "pad digits")]
         #[test_case("01",            F::Repeat(repetition_input()),             "0101"     ; "repetition input ")]
         #[test_case("01",            F::Repeat(repetition_value()),             "abcabc"   ; "repetition value ")]
         #[test_case("",              F::LocalCounter,                           "1"        ; "local counter")]
@@ -481,10 +550,11 @@ mod tests {
         #[test_case("",              F::RandomNumber(number_range_zero()),      "0"        ; "random number")]
         #[test_case("",              F::RandomUuid,                             ""         ; "random uuid")]
         fn ok(input: &str, filter: Filter, output: &str) {
+            let random = Context::random_fixture();
             match filter {
                 Filter::CanonicalPath => {
                     let real_working_dir = std::env::current_dir().unwrap(); // Canonical path filter actually checks existence of the directory
-                    let mut context = Context::fixture();
+                    let mut context = Context::fixture(&random);
                     let output = output.replace(
                         context.working_dir.to_str().unwrap(),
                         real_working_dir.to_str().unwrap(),
@@ -493,11 +563,15 @@ mod tests {
                     assert_eq!(filter.eval(input.into(), &context), Ok(output))
                 }
                 Filter::RandomUuid => {
-                    assert_uuid(&filter.eval(input.into(), &Context::fixture()).unwrap());
+                    assert_uuid(
+                        &filter
+                            .eval(input.into(), &Context::fixture(&random))
+                            .unwrap(),
+                    );
                 }
                 _ => {
                     assert_eq!(
-                        filter.eval(input.into(), &Context::fixture()),
+                        filter.eval(input.into(), &Context::fixture(&random)),
                         Ok(output.into())
                     )
                 }
@@ -514,12 +588,18 @@ mod tests {
     #[test_case(F::RemoveLastName,                         "Remove last name"                    ; "remove last name")]
     #[test_case(F::FileName,                               "File name"                           ; "file name")]
     #[test_case(F::LastName,                               "Last name"                           ; "last name")]
-    #[test_case(F::BaseName,                               "Base name"                           ; "base name")]
-    #[test_case(F::RemoveExtension,                        "Remove extension"                    ; "remove extension")]
-    #[test_case(F::Extension,                              "Extension"                           ; "extension")]
-    #[test_case(F::ExtensionWithDot,                       "Extension with dot"                  ; "extension with dot")]
+    #[test_case(F::BaseName(1),                            "Base name"                           ; "base name")]
+    #[test_case(F::BaseName(2),                            "Base name without 2 extensions"      ; "base name levels")]
+    #[test_case(F::RemoveExtension(1),                     "Remove extension"                    ; "remove extension")]
+    #[test_case(F::RemoveExtension(2),                     "Remove 2 extensions"                 ; "remove extension levels")]
+    #[test_case(F::Extension(1),                           "Extension"                           ; "extension")]
+    #[test_case(F::Extension(2),                           "2 extensions"                        ; "extension levels")]
+    #[test_case(F::ExtensionWithDot(1),                    "Extension with dot"                  ; "extension with dot")]
+    #[test_case(F::ExtensionWithDot(2),                    "2 extensions with dot"               ; "extension with dot levels")]
     #[test_case(F::EnsureTrailDirSeparator,                "Ensure trailing directory separator" ; "ensure trail dir separator")]
     #[test_case(F::RemoveTrailDirSeparator,                "Remove trailing directory separator" ; "remove trail dir separator")]
+    #[test_case(F::ExpandHomeAndVars,                      "Expand home and variables"           ; "expand home and vars")]
+    #[test_case(F::ContractHomeDir,                        "Contract home directory"             ; "contract home dir")]
     #[test_case(F::Substring(index_range_at()),            "Substring from 2..2"                 ; "substring at")]
     #[test_case(F::Substring(index_range_from()),          "Substring from 2.."                  ; "substring from")]
     #[test_case(F::Substring(index_range_between()),       "Substring from 2..3"                 ; "substring between")]
@@ -558,10 +638,13 @@ mod tests {
     #[test_case(F::ToUppercase,                          "To uppercase"                                    ; "to uppercase")]
     #[test_case(F::ToAscii,                              "To ASCII"                                        ; "to ascii")]
     #[test_case(F::RemoveNonAscii,                       "Remove non-ASCII"                                ; "remove non-ascii")]
+    #[test_case(F::ReverseChars,                         "Reverse characters"                              ; "reverse chars")]
+    #[test_case(F::ReverseFields(separator_default()),   "Reverse fields (regular expression '\\s+' separator)" ; "reverse fields")]
     #[test_case(F::LeftPad(padding_fixed()),             "Left pad with 'abcd'"                            ; "left pad fixed")]
     #[test_case(F::LeftPad(padding_repeated()),          "Left pad with 2x 'abc'"                          ; "left pad repeated")]
     #[test_case(F::RightPad(padding_fixed()),            "Right pad with 'abcd'"                           ; "right pad fixed")]
     #[test_case(F::RightPad(padding_repeated()),         "Right pad with 2x 'abc'"                         ; "right pad repeated")]
+    #[test_case(F::PadDigits(digit_padding()),           "Zero-pad first number to 3 digits"               ; "pad digits")]
     #[test_case(F::Repeat(repetition_input()),           "Repeat 2x"                                       ; "repetition input ")]
     #[test_case(F::Repeat(repetition_value()),           "Repeat 2x 'abc'"                                 ; "repetition value ")]
     #[test_case(F::LocalCounter,                         "Local counter"                                   ; "local counter")]
@@ -593,10 +676,14 @@ mod tests {
     fn field_default() -> Field {
         Field {
             index: 1,
-            separator: Separator::Regex("\\s+".into()),
+            separator: separator_default(),
         }
     }
 
+    fn separator_default() -> Separator {
+        Separator::Regex("\\s+".into())
+    }
+
     fn field_string() -> Field {
         Field {
             index: 1,
@@ -685,6 +772,13 @@ mod tests {
         Padding::Repeated(repetition_value())
     }
 
+    fn digit_padding() -> DigitPadding {
+        DigitPadding {
+            width: 3,
+            runs: DigitRuns::First,
+        }
+    }
+
     fn repetition_input() -> Repetition {
         Repetition {
             count: 2,
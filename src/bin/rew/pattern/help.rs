@@ -37,6 +37,8 @@ pub const FILTERS: &str = indoc! {r#"
   `b`  Base name           `e`  Extension
   `B`  Remove extension    `E`  Extension with dot
 
+  `bN`, `BN`, `eN`, `EN`   Same as above with `N` extensions (e.g. `tar.gz`)
+
   `w`  Working directory
 
   `a`  Absolute path       `p`  Normalized path
@@ -45,6 +47,9 @@ pub const FILTERS: &str = indoc! {r#"
   `z`  Ensure trailing directory separator
   `Z`  Remove trailing directory separator
 
+  `x`  Expand `~`, `~user` and `$VAR`
+  `X`  Contract home directory to `~`
+
 # SUBSTRING
 
   `#A-B`  From `A` to `B`         (`A`, `B` = inclusive 1-based index)
@@ -92,9 +97,13 @@ pub const FILTERS: &str = indoc! {r#"
   `^`  To uppercase    `i`   To ASCII
   `v`  To lowercase    `I`   Remove non-ASCII chars
 
+  `y`  Reverse characters
+  `Y`  Reverse fields, default separator
+
   `*N`    Repeat `N` times
   `<<M`   Left pad with `M`            (`>>` or `>` to right pad)
   `<N:M`  Left pad `N` times with `M`    (`:` = any delimiter char)
+  `nN`    Zero-pad numbers to `N` digits  (`nN^` = first, `nN$` = last)
 
 # GENERATORS
 
@@ -2,8 +2,9 @@ use crate::pattern::filter::Filter;
 use crate::pattern::parser::{Item, ParsedItem, Parser};
 
 mod char;
+mod digits;
 pub mod error;
-mod escape;
+pub mod escape;
 pub mod eval;
 mod explain;
 mod field;
@@ -64,7 +65,7 @@ impl Pattern {
         })
     }
 
-    pub fn eval(&self, input: &str, context: &eval::Context) -> eval::Result<String> {
+    pub fn eval(&self, input: &str, context: &eval::Context) -> eval::Result<'_, String> {
         let mut output = String::new();
 
         for item in &self.items {
@@ -198,8 +199,9 @@ mod tests {
                 value: Filter::CanonicalPath,
                 range: 1..2,
             }]))]);
+            let random = Context::random_fixture();
             assert_eq!(
-                pattern.eval("dir/file.ext", &Context::fixture()),
+                pattern.eval("dir/file.ext", &Context::fixture(&random)),
                 Err(Error {
                     kind: ErrorKind::CanonicalizationFailed(AnyString::any()),
                     value: "dir/file.ext".into(),
@@ -217,7 +219,8 @@ mod tests {
         #[test_case("a/b", complex_expr(),  Some('\''), "1 'a' 2 'B' 3" ; "quoted complex expression")]
         fn ok(input: &str, items: Vec<ParsedItem>, quotes: Option<char>, output: &str) {
             let pattern = Pattern::from(items);
-            let mut context = Context::fixture();
+            let random = Context::random_fixture();
+            let mut context = Context::fixture(&random);
             context.expression_quotes = quotes;
             assert_eq!(pattern.eval(input, &context), Ok(output.into()));
         }
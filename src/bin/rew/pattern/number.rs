@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use crate::pattern::range::{Range, RangeType};
 
@@ -20,14 +20,14 @@ impl RangeType for NumberRangeType {
 }
 
 impl NumberRange {
-    pub fn random(&self) -> Number {
+    pub fn random<R: Rng>(&self, rng: &mut R) -> Number {
         let start = self.start();
         let end = self.end().unwrap_or(Number::MAX);
 
         if start == 0 && end == Number::MAX {
-            thread_rng().gen() // gen_range(start..=end) would cause an overflow in rand lib
+            rng.gen() // gen_range(start..=end) would cause an overflow in rand lib
         } else {
-            thread_rng().gen_range(start..=end)
+            rng.gen_range(start..=end)
         }
     }
 }
@@ -85,23 +85,41 @@ mod tests {
     }
 
     mod random {
+        use rand::SeedableRng;
         use test_case::test_case;
 
         use super::*;
+        use crate::pattern::eval::Random;
 
         const MAX: Number = Number::MAX;
 
         #[test_case(0,   Some(0), 0   ; "lowest")]
         #[test_case(MAX, None,    MAX ; "highest")]
         fn certain(start: Number, end: Option<Number>, result: Number) {
-            assert_eq!(NumberRange::new(start, end).random(), result);
+            let mut rng = Random::from_entropy();
+            assert_eq!(NumberRange::new(start, end).random(&mut rng), result);
         }
 
         #[test_case(0, Some(MAX)     ; "from 0 to max")] // Should not overflow
         #[test_case(1, Some(MAX)     ; "from 1 to max")]
         #[test_case(0, Some(MAX - 1) ; "from 0 to max-1")]
         fn uncertain(start: Number, end: Option<Number>) {
-            NumberRange::new(start, end).random();
+            NumberRange::new(start, end).random(&mut Random::from_entropy());
+        }
+
+        #[test_case(0,  None,     [7424550030962593201, 1482817706323250795, 11004592982271133285] ; "full")]
+        #[test_case(10, Some(20), [14, 16, 12]                                                  ; "between")]
+        fn seeded(start: Number, end: Option<Number>, results: [Number; 3]) {
+            let range = NumberRange::new(start, end);
+            let mut rng = Random::seed_from_u64(1);
+            assert_eq!(
+                [
+                    range.random(&mut rng),
+                    range.random(&mut rng),
+                    range.random(&mut rng)
+                ],
+                results
+            );
         }
     }
 
@@ -288,7 +288,7 @@ mod tests {
             vec![Parsed {
                 value: Item::Expression(vec![
                     Parsed {
-                        value: Filter::Extension,
+                        value: Filter::Extension(1),
                         range: 1..2,
                     },
                     Parsed {
@@ -333,7 +333,7 @@ mod tests {
                 Parsed {
                     value: Item::Expression(vec![
                         Parsed {
-                            value: Filter::Extension,
+                            value: Filter::Extension(1),
                             range: 16..17,
                         },
                         Parsed {
@@ -1,3 +1,4 @@
+use std::env;
 use std::ffi::OsStr;
 use std::path::{is_separator, Component, Path, PathBuf, MAIN_SEPARATOR};
 
@@ -39,7 +40,7 @@ pub fn canonicalize(value: String, working_dir: &Path) -> BaseResult<String> {
             if cfg!(windows)
                 && result.ends_with(MAIN_SEPARATOR)
                 && !matches!(
-                    Path::new(&result).components().last(),
+                    Path::new(&result).components().next_back(),
                     Some(Component::RootDir)
                 )
             {
@@ -110,7 +111,7 @@ pub fn normalize(value: &str) -> BaseResult<String> {
 
 pub fn get_parent_directory(value: String) -> BaseResult<String> {
     let path = Path::new(&value);
-    match path.components().last() {
+    match path.components().next_back() {
         Some(Component::Prefix(_)) => into_string(PathBuf::from(value).join(Component::RootDir)),
         Some(Component::RootDir) => Ok(value),
         Some(Component::CurDir | Component::ParentDir) | None => {
@@ -140,7 +141,7 @@ pub fn get_file_name(value: &str) -> BaseResult<String> {
 }
 
 pub fn get_last_name(value: &str) -> BaseResult<String> {
-    match Path::new(value).components().last() {
+    match Path::new(value).components().next_back() {
         Some(component @ (Component::Normal(_) | Component::CurDir | Component::ParentDir)) => {
             to_string(&component)
         }
@@ -148,29 +149,51 @@ pub fn get_last_name(value: &str) -> BaseResult<String> {
     }
 }
 
-pub fn get_base_name(value: &str) -> BaseResult<String> {
-    to_string(Path::new(value).file_stem().unwrap_or_default())
+pub fn get_base_name(value: &str, levels: usize) -> BaseResult<String> {
+    let file_name = to_str(Path::new(value).file_name().unwrap_or_default())?;
+    Ok(split_extensions(file_name, levels).0.to_string())
 }
 
-pub fn remove_extension(mut value: String) -> BaseResult<String> {
-    if let Some(extension_len) = Path::new(&value).extension().map(OsStr::len) {
-        value.replace_range((value.len() - extension_len - 1).., "");
+pub fn remove_extension(mut value: String, levels: usize) -> BaseResult<String> {
+    for _ in 0..levels {
+        if let Some(extension_len) = Path::new(&value).extension().map(OsStr::len) {
+            value.replace_range((value.len() - extension_len - 1).., "");
+        } else {
+            break;
+        }
     }
     Ok(value)
 }
 
-pub fn get_extension(value: &str) -> BaseResult<String> {
-    to_string(Path::new(value).extension().unwrap_or_default())
+pub fn get_extension(value: &str, levels: usize) -> BaseResult<String> {
+    let file_name = to_str(Path::new(value).file_name().unwrap_or_default())?;
+    let extension = split_extensions(file_name, levels).1;
+    Ok(extension.strip_prefix('.').unwrap_or(extension).to_string())
 }
 
-pub fn get_extension_with_dot(value: &str) -> BaseResult<String> {
-    let mut result = get_extension(value)?;
+pub fn get_extension_with_dot(value: &str, levels: usize) -> BaseResult<String> {
+    let mut result = get_extension(value, levels)?;
     if !result.is_empty() {
         result.insert(0, '.');
     }
     Ok(result)
 }
 
+// Splits file name into base name and up to N extensions (including their leading dot).
+fn split_extensions(file_name: &str, levels: usize) -> (&str, &str) {
+    let mut base_name_len = file_name.len();
+
+    for _ in 0..levels {
+        if let Some(extension) = Path::new(&file_name[..base_name_len]).extension() {
+            base_name_len -= extension.len() + 1;
+        } else {
+            break;
+        }
+    }
+
+    file_name.split_at(base_name_len)
+}
+
 pub fn ensure_trailing_dir_separator(mut value: String) -> String {
     match value.chars().last() {
         Some(last_char) if is_separator(last_char) => {
@@ -195,6 +218,139 @@ pub fn remove_trailing_dir_separator(mut value: String) -> String {
     value
 }
 
+pub fn expand_home_and_vars<V, U>(
+    value: &str,
+    home_dir: Option<&Path>,
+    get_var: V,
+    get_user_home_dir: U,
+) -> BaseResult<String>
+where
+    V: Fn(&str) -> Option<String>,
+    U: Fn(&str) -> Option<PathBuf>,
+{
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    if let Some(tilde_rest) = value.strip_prefix('~') {
+        let user_len = tilde_rest.find(is_separator).unwrap_or(tilde_rest.len());
+        let (user, after_user) = tilde_rest.split_at(user_len);
+
+        let dir = if user.is_empty() {
+            home_dir
+                .map(Path::to_path_buf)
+                .ok_or_else(|| ErrorKind::ExpansionFailed("Home directory is unknown".into()))?
+        } else {
+            get_user_home_dir(user)
+                .ok_or_else(|| ErrorKind::ExpansionFailed(format!("User '{}' not found", user)))?
+        };
+
+        result.push_str(to_str(&dir)?);
+        rest = after_user;
+    }
+
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+        let after_dollar = &rest[(index + 1)..];
+
+        let (name, after_name) = if let Some(braced) = after_dollar.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[(end + 1)..]),
+                None => ("", after_dollar),
+            }
+        } else {
+            let name_len = after_dollar
+                .find(|char: char| !is_var_name_char(char))
+                .unwrap_or(after_dollar.len());
+            after_dollar.split_at(name_len)
+        };
+
+        if is_var_name(name) {
+            let value = get_var(name).ok_or_else(|| {
+                ErrorKind::ExpansionFailed(format!("Environment variable '{}' is not set", name))
+            })?;
+            result.push_str(&value);
+            rest = after_name;
+        } else {
+            // Not a variable reference, keep `$` as is
+            result.push('$');
+            rest = after_dollar;
+        }
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn is_var_name(name: &str) -> bool {
+    match name.chars().next() {
+        Some(first_char) => !first_char.is_ascii_digit() && name.chars().all(is_var_name_char),
+        None => false,
+    }
+}
+
+fn is_var_name_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+pub fn contract_home_dir(value: String, home_dir: Option<&Path>) -> BaseResult<String> {
+    if let Some(home_dir) = home_dir {
+        if let Ok(rest) = Path::new(&value).strip_prefix(home_dir) {
+            let mut result = String::from("~");
+            if !rest.as_os_str().is_empty() {
+                result.push(MAIN_SEPARATOR);
+                result.push_str(to_str(rest)?);
+            }
+            return Ok(result);
+        }
+    }
+    Ok(value)
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" })
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(unix)]
+pub fn user_home_dir(user: &str) -> Option<PathBuf> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+    use std::{mem, ptr};
+
+    let user = CString::new(user).ok()?;
+    let mut buffer = vec![0 as libc::c_char; 1024];
+
+    loop {
+        let mut passwd: libc::passwd = unsafe { mem::zeroed() };
+        let mut result = ptr::null_mut();
+
+        let code = unsafe {
+            libc::getpwnam_r(
+                user.as_ptr(),
+                &mut passwd,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+
+        if code == libc::ERANGE {
+            buffer.resize(buffer.len() * 2, 0);
+        } else if code != 0 || result.is_null() || passwd.pw_dir.is_null() {
+            return None;
+        } else {
+            let dir = unsafe { CStr::from_ptr(passwd.pw_dir) };
+            return Some(PathBuf::from(OsStr::from_bytes(dir.to_bytes())));
+        }
+    }
+}
+
+#[cfg(windows)]
+pub fn user_home_dir(_user: &str) -> Option<PathBuf> {
+    None
+}
+
 pub fn into_string(value: PathBuf) -> BaseResult<String> {
     match value.into_os_string().into_string() {
         Ok(result) => Ok(result),
@@ -222,11 +378,11 @@ mod tests {
 
     use super::*;
 
-    #[                 test_case("",             "{work}"           ; "empty")]
-    #[cfg_attr(unix,   test_case("file.ext",     "{work}/file.ext"  ; "relative"))]
-    #[cfg_attr(unix,   test_case("/file.ext",    "/file.ext"        ; "absolute"))]
-    #[cfg_attr(window, test_case("file.ext",     "{work}\\file.ext" ; "relative"))]
-    #[cfg_attr(window, test_case("C:\\file.ext", "C:\\file.ext"     ; "absolute"))]
+    #[                  test_case("",             "{work}"           ; "empty")]
+    #[cfg_attr(unix,    test_case("file.ext",     "{work}/file.ext"  ; "relative"))]
+    #[cfg_attr(unix,    test_case("/file.ext",    "/file.ext"        ; "absolute"))]
+    #[cfg_attr(windows, test_case("file.ext",     "{work}\\file.ext" ; "relative"))]
+    #[cfg_attr(windows, test_case("C:\\file.ext", "C:\\file.ext"     ; "absolute"))]
     fn to_absolute(input: &str, output: &str) {
         let working_dir = std::env::current_dir().unwrap();
         assert_eq!(
@@ -235,11 +391,11 @@ mod tests {
         );
     }
 
-    #[                 test_case("",                     ""             ; "empty")]
-    #[cfg_attr(unix,   test_case("file.ext",             "file.ext"     ; "relative" ))]
-    #[cfg_attr(unix,   test_case("{work}/../file.ext",   "../file.ext"  ; "absolute"))]
-    #[cfg_attr(window, test_case("file.ext",             "file.ext"     ; "relative"))]
-    #[cfg_attr(window, test_case("{work}\\..\\file.ext", "..\\file.ext" ; "absolute"))]
+    #[                  test_case("",                     ""             ; "empty")]
+    #[cfg_attr(unix,    test_case("file.ext",             "file.ext"     ; "relative" ))]
+    #[cfg_attr(unix,    test_case("{work}/../file.ext",   "../file.ext"  ; "absolute"))]
+    #[cfg_attr(windows, test_case("file.ext",             "file.ext"     ; "relative"))]
+    #[cfg_attr(windows, test_case("{work}\\..\\file.ext", "..\\file.ext" ; "absolute"))]
     fn to_relative(input: &str, output: &str) {
         let working_dir = std::env::current_dir().unwrap();
         assert_eq!(
@@ -509,7 +665,7 @@ mod tests {
     #[cfg_attr(windows, test_case("..\\file.ext",  "file" ; "double dot parent"))]
     #[cfg_attr(windows, test_case("dir\\file.ext", "file" ; "name parent"))]
     fn get_base_name(input: &str, output: &str) {
-        assert_eq!(super::get_base_name(input), Ok(output.into()));
+        assert_eq!(super::get_base_name(input, 1), Ok(output.into()));
     }
 
     #[                  test_case("",              ""          ; "empty")]
@@ -528,7 +684,7 @@ mod tests {
     #[cfg_attr(windows, test_case("..\\file.ext",  "..\\file"  ; "double dot parent"))]
     #[cfg_attr(windows, test_case("dir\\file.ext", "dir\\file" ; "name parent"))]
     fn remove_extension(input: &str, output: &str) {
-        assert_eq!(super::remove_extension(input.into()), Ok(output.into()));
+        assert_eq!(super::remove_extension(input.into(), 1), Ok(output.into()));
     }
 
     #[                  test_case("",             ""     ; "empty")]
@@ -547,7 +703,7 @@ mod tests {
     #[cfg_attr(windows, test_case("..\\file.ext",  "ext" ; "double dot parent"))]
     #[cfg_attr(windows, test_case("dir\\file.ext", "ext" ; "name parent"))]
     fn get_extension(input: &str, output: &str) {
-        assert_eq!(super::get_extension(input), Ok(output.into()));
+        assert_eq!(super::get_extension(input, 1), Ok(output.into()));
     }
 
     #[                  test_case("",             ""      ; "empty")]
@@ -566,7 +722,34 @@ mod tests {
     #[cfg_attr(windows, test_case("..\\file.ext",  ".ext" ; "double dot parent"))]
     #[cfg_attr(windows, test_case("dir\\file.ext", ".ext" ; "name parent"))]
     fn get_extension_with_dot(input: &str, output: &str) {
-        assert_eq!(super::get_extension_with_dot(input), Ok(output.into()));
+        assert_eq!(super::get_extension_with_dot(input, 1), Ok(output.into()));
+    }
+
+    #[test_case("a.tar.gz",       0, "a.tar.gz", "a.tar.gz", "",       ""        ; "zero")]
+    #[test_case("a.tar.gz",       1, "a.tar",    "a.tar",    "gz",     ".gz"     ; "one")]
+    #[test_case("a.tar.gz",       2, "a",        "a",        "tar.gz", ".tar.gz" ; "two")]
+    #[test_case("a.tar.gz",       3, "a",        "a",        "tar.gz", ".tar.gz" ; "more than available")]
+    #[test_case("dir.x/a.tar.gz", 2, "a",        "dir.x/a",  "tar.gz", ".tar.gz" ; "dir with dot")]
+    #[test_case(".config.tar",    2, ".config",  ".config",  "tar",    ".tar"    ; "hidden file")]
+    #[test_case("file",           2, "file",     "file",     "",       ""        ; "no extension")]
+    fn extension_levels(
+        input: &str,
+        levels: usize,
+        base_name: &str,
+        without_extension: &str,
+        extension: &str,
+        extension_with_dot: &str,
+    ) {
+        assert_eq!(super::get_base_name(input, levels), Ok(base_name.into()));
+        assert_eq!(
+            super::remove_extension(input.into(), levels),
+            Ok(without_extension.into())
+        );
+        assert_eq!(super::get_extension(input, levels), Ok(extension.into()));
+        assert_eq!(
+            super::get_extension_with_dot(input, levels),
+            Ok(extension_with_dot.into())
+        );
     }
 
     #[cfg_attr(unix,    test_case("",      "/"     ; "empty"))]
@@ -599,6 +782,78 @@ mod tests {
         assert_eq!(super::remove_trailing_dir_separator(input.into()), output);
     }
 
+    mod expand_home_and_vars {
+        use test_case::test_case;
+
+        use super::*;
+
+        #[test_case("",             ""                  ; "empty")]
+        #[test_case("a/b",          "a/b"               ; "no expansion")]
+        #[test_case("~",            "/home/user"        ; "home")]
+        #[test_case("~/a",          "/home/user/a"      ; "home prefix")]
+        #[test_case("a/~",          "a/~"               ; "home not prefix")]
+        #[test_case("~bob",         "/home/bob"         ; "user home")]
+        #[test_case("~bob/a",       "/home/bob/a"       ; "user home prefix")]
+        #[test_case("$VAR/a",       "value/a"           ; "variable")]
+        #[test_case("${VAR}a",      "valuea"            ; "braced variable")]
+        #[test_case("$VAR$VAR",     "valuevalue"        ; "adjacent variables")]
+        #[test_case("~/$VAR",       "/home/user/value"  ; "home and variable")]
+        #[test_case("$",            "$"                 ; "dollar")]
+        #[test_case("$1/$-/$.",     "$1/$-/$."          ; "dollar not variable")]
+        #[test_case("${}/${1}",     "${}/${1}"          ; "braces not variable")]
+        #[test_case("${VAR",        "${VAR"             ; "unterminated braces")]
+        fn ok(input: &str, output: &str) {
+            assert_eq!(
+                expand(input, Some(Path::new("/home/user"))),
+                Ok(output.into())
+            );
+        }
+
+        #[test_case("~",        None,                          "Home directory is unknown"               ; "home unknown")]
+        #[test_case("~alice",   Some(Path::new("/home/user")), "User 'alice' not found"                  ; "user not found")]
+        #[test_case("$OTHER",   Some(Path::new("/home/user")), "Environment variable 'OTHER' is not set" ; "variable not set")]
+        #[test_case("${OTHER}", Some(Path::new("/home/user")), "Environment variable 'OTHER' is not set" ; "braced variable not set")]
+        fn err(input: &str, home_dir: Option<&Path>, reason: &str) {
+            assert_eq!(
+                expand(input, home_dir),
+                Err(ErrorKind::ExpansionFailed(reason.into()))
+            );
+        }
+
+        fn expand(value: &str, home_dir: Option<&Path>) -> BaseResult<String> {
+            super::expand_home_and_vars(
+                value,
+                home_dir,
+                |name| (name == "VAR").then(|| "value".into()),
+                |user| (user == "bob").then(|| "/home/bob".into()),
+            )
+        }
+    }
+
+    #[cfg_attr(unix,    test_case("/home/user",            Some("/home/user"),      "~"                   ; "home"))]
+    #[cfg_attr(unix,    test_case("/home/user/",           Some("/home/user"),      "~"                   ; "home separator"))]
+    #[cfg_attr(unix,    test_case("/home/user/a/b",        Some("/home/user"),      "~/a/b"               ; "home prefix"))]
+    #[cfg_attr(unix,    test_case("/home/username",        Some("/home/user"),      "/home/username"      ; "home name prefix"))]
+    #[cfg_attr(unix,    test_case("a/home/user",           Some("/home/user"),      "a/home/user"         ; "relative"))]
+    #[cfg_attr(unix,    test_case("/home/user/a",          None,                    "/home/user/a"        ; "home unknown"))]
+    #[cfg_attr(windows, test_case("C:\\Users\\user",       Some("C:\\Users\\user"), "~"                   ; "home"))]
+    #[cfg_attr(windows, test_case("C:\\Users\\user\\a\\b", Some("C:\\Users\\user"), "~\\a\\b"             ; "home prefix"))]
+    #[cfg_attr(windows, test_case("C:\\Users\\username",   Some("C:\\Users\\user"), "C:\\Users\\username" ; "home name prefix"))]
+    #[cfg_attr(windows, test_case("C:\\Users\\user\\a",    None,                    "C:\\Users\\user\\a"  ; "home unknown"))]
+    fn contract_home_dir(input: &str, home_dir: Option<&str>, output: &str) {
+        assert_eq!(
+            super::contract_home_dir(input.into(), home_dir.map(Path::new)),
+            Ok(output.into())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn user_home_dir() {
+        assert!(super::user_home_dir("root").is_some());
+        assert_eq!(super::user_home_dir("rew-non-existent-user"), None);
+    }
+
     #[test_case("abc",                     Ok("abc".into())             ; "utf-8")]
     #[test_case(make_non_utf8_os_string(), Err(ErrorKind::InputNotUtf8) ; "non utf-8")]
     fn into_string<T: Into<PathBuf>>(input: T, result: BaseResult<String>) {
@@ -1,8 +1,13 @@
-use uuid::Uuid;
+use rand::Rng;
+use uuid::{Builder, Uuid, Variant, Version};
 
-pub fn random_uuid() -> String {
+pub fn random_uuid<R: Rng>(rng: &mut R) -> String {
+    let uuid = Builder::from_bytes(rng.gen())
+        .set_variant(Variant::RFC4122)
+        .set_version(Version::Random)
+        .build();
     let mut buffer = Uuid::encode_buffer();
-    let str = Uuid::new_v4().to_hyphenated().encode_lower(&mut buffer);
+    let str = uuid.to_hyphenated().encode_lower(&mut buffer);
     (*str).to_string()
 }
 
@@ -10,13 +15,30 @@ pub fn random_uuid() -> String {
 pub fn assert_uuid(value: &str) {
     let regex_str = "^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$";
     let regex = regex::Regex::new(regex_str).unwrap();
-    assert!(regex.is_match(&value), "{} is UUID v4", value);
+    assert!(regex.is_match(value), "{} is UUID v4", value);
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::SeedableRng;
+
+    use crate::pattern::eval::Random;
+
     #[test]
     fn random_uuid() {
-        super::assert_uuid(&super::random_uuid());
+        super::assert_uuid(&super::random_uuid(&mut Random::from_entropy()));
+    }
+
+    #[test]
+    fn seeded() {
+        let mut rng = Random::seed_from_u64(1);
+        assert_eq!(
+            super::random_uuid(&mut rng),
+            "b1ea6bd8-6503-42dc-a553-583617e08e42"
+        );
+        assert_eq!(
+            super::random_uuid(&mut rng),
+            "131b08b0-71b2-435e-a3e5-a10be2bf1dda"
+        );
     }
 }
@@ -0,0 +1,206 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Result, Write};
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+pub fn make_temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", Uuid::new_v4().to_simple()));
+    path.with_file_name(name)
+}
+
+// Writes to a temporary file which replaces the target file only after a successful commit.
+// The target file is left intact when the atomic file is dropped without commit.
+pub struct AtomicFile {
+    path: PathBuf,
+    temp_path: PathBuf,
+    writer: Option<BufWriter<File>>,
+}
+
+impl AtomicFile {
+    pub fn create(path: &Path, append: bool) -> Result<Self> {
+        // Write through symbolic link, instead of replacing it with a regular file.
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let temp_path = make_temp_path(&path);
+
+        let file = if append && path.exists() {
+            fs::copy(&path, &temp_path)?;
+            OpenOptions::new().append(true).open(&temp_path)?
+        } else {
+            let file = File::create(&temp_path)?;
+            if let Ok(metadata) = path.metadata() {
+                file.set_permissions(metadata.permissions())?;
+            }
+            file
+        };
+
+        Ok(Self {
+            path,
+            temp_path,
+            writer: Some(BufWriter::new(file)),
+        })
+    }
+
+    pub fn commit(mut self) -> Result<()> {
+        self.writer().flush()?;
+        self.writer().get_ref().sync_all()?;
+        fs::rename(&self.temp_path, &self.path)?;
+        self.writer = None; // Prevents temp file removal on drop.
+        Ok(())
+    }
+
+    fn writer(&mut self) -> &mut BufWriter<File> {
+        self.writer
+            .as_mut()
+            .expect("Atomic file was already committed")
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer().flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.writer.take().is_some() {
+            fs::remove_file(&self.temp_path).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn make_temp_path() {
+        let path = super::make_temp_path(Path::new("dir/file.txt"));
+        let name = path.file_name().unwrap().to_str().unwrap();
+
+        assert_eq!(path.parent(), Some(Path::new("dir")));
+        assert!(name.starts_with("file.txt."));
+        assert!(name.ends_with(".tmp"));
+    }
+
+    mod atomic_file {
+        use super::*;
+
+        #[test]
+        fn create() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+
+            let mut atomic_file = AtomicFile::create(file.path(), false).unwrap();
+            write!(atomic_file, "new").unwrap();
+            atomic_file.commit().unwrap();
+
+            file.assert("new");
+            assert_eq!(dir.read_dir().unwrap().count(), 1);
+        }
+
+        #[test]
+        fn replace() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            file.write_str("old").unwrap();
+
+            let mut atomic_file = AtomicFile::create(file.path(), false).unwrap();
+            write!(atomic_file, "new").unwrap();
+            file.assert("old");
+
+            atomic_file.commit().unwrap();
+            file.assert("new");
+        }
+
+        #[test]
+        fn append() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            file.write_str("old").unwrap();
+
+            let mut atomic_file = AtomicFile::create(file.path(), true).unwrap();
+            write!(atomic_file, "new").unwrap();
+            file.assert("old");
+
+            atomic_file.commit().unwrap();
+            file.assert("oldnew");
+        }
+
+        #[test]
+        fn append_missing() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+
+            let mut atomic_file = AtomicFile::create(file.path(), true).unwrap();
+            write!(atomic_file, "new").unwrap();
+            atomic_file.commit().unwrap();
+
+            file.assert("new");
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn keep_permissions() {
+            use std::os::unix::fs::PermissionsExt;
+
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            file.write_str("old").unwrap();
+            fs::set_permissions(file.path(), fs::Permissions::from_mode(0o640)).unwrap();
+
+            let mut atomic_file = AtomicFile::create(file.path(), false).unwrap();
+            write!(atomic_file, "new").unwrap();
+            atomic_file.commit().unwrap();
+
+            file.assert("new");
+            let mode = file.path().metadata().unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn write_through_symlink() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            let link = dir.child("link");
+            file.write_str("old").unwrap();
+            link.symlink_to_file(file.path()).unwrap();
+
+            let mut atomic_file = AtomicFile::create(link.path(), false).unwrap();
+            write!(atomic_file, "new").unwrap();
+            atomic_file.commit().unwrap();
+
+            file.assert("new");
+            assert!(link
+                .path()
+                .symlink_metadata()
+                .unwrap()
+                .file_type()
+                .is_symlink());
+        }
+
+        #[test]
+        fn drop_without_commit() {
+            let dir = TempDir::new().unwrap();
+            let file = dir.child("file");
+            file.write_str("old").unwrap();
+
+            let mut atomic_file = AtomicFile::create(file.path(), false).unwrap();
+            write!(atomic_file, "new").unwrap();
+            drop(atomic_file);
+
+            file.assert("old");
+            assert_eq!(dir.read_dir().unwrap().count(), 1);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::io::{Result, Write};
 
+use indoc::indoc;
 use lazy_static::lazy_static;
 use termcolor::{Buffer, Color, WriteColor};
 
@@ -17,6 +18,20 @@ const PRIMARY_COLOR: Color = Color::Yellow;
 const SECONDARY_COLOR: Color = Color::Cyan;
 const CODE_COLOR: Color = Color::Green;
 
+pub const EXIT_CODES: &str = indoc! {"
+# EXIT CODES
+
+  `0`  Success
+  `1`  I/O error (reading input, writing output, transferring a path)
+  `2`  Invalid command line arguments
+  `3`  Pattern parse error (`rew`)
+  `4`  Pattern evaluation error (`rew`)
+  `5`  Conflicting or missing paths found by `--check` (`mvb`, `cpb`, `lnb`)
+
+With `--fail-at-end` flag, the same codes are returned once all input is processed.
+When interrupted by a signal (e.g. `Ctrl+C`), shell reports `128 + signal number` (e.g. `130`).
+"};
+
 lazy_static! {
     static ref COLORED_HELP_ENABLED: bool = atty::is(atty::Stream::Stdout)
         && std::env::args().any(|arg| arg == "-h" || arg == "--help");
@@ -114,6 +129,11 @@ mod tests {
             $> ls -la # Shell comment
     "};
 
+    #[test]
+    fn highlight_exit_codes() {
+        assert_gt!(super::highlight_to_string(EXIT_CODES).unwrap().len(), 0);
+    }
+
     #[test]
     fn highlight_to_string() {
         assert_gt!(super::highlight_to_string(SAMPLE_HELP).unwrap().len(), 0);
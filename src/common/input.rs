@@ -1,6 +1,22 @@
-use std::io::{BufRead, Result};
+use std::io::{BufRead, Result, Write};
+use std::mem;
 
-use crate::utils::str_from_utf8;
+use termcolor::WriteColor;
+
+use crate::output::write_hint;
+use crate::utils::{escape_bytes, make_utf8_error};
+
+pub const INVALID_UTF8_CHOICES: &[&str] = &[ERROR, LOSSY, ESCAPE, SKIP];
+
+const ERROR: &str = "error";
+const LOSSY: &str = "lossy";
+const ESCAPE: &str = "escape";
+const SKIP: &str = "skip";
+
+#[cfg(windows)]
+const EOF_KEYS: &str = "Ctrl+Z, Enter";
+#[cfg(not(windows))]
+const EOF_KEYS: &str = "Ctrl+D";
 
 pub enum Terminator {
     Newline { required: bool },
@@ -8,22 +24,85 @@ pub enum Terminator {
     None,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvalidUtf8 {
+    Error,
+    Lossy,
+    Escape,
+    Skip,
+}
+
+pub fn parse_invalid_utf8(string: &str) -> std::result::Result<InvalidUtf8, &'static str> {
+    match string {
+        ERROR => Ok(InvalidUtf8::Error),
+        LOSSY => Ok(InvalidUtf8::Lossy),
+        ESCAPE => Ok(InvalidUtf8::Escape),
+        SKIP => Ok(InvalidUtf8::Skip),
+        _ => Err("invalid value"),
+    }
+}
+
+pub fn is_stdin_tty() -> bool {
+    atty::is(atty::Stream::Stdin)
+}
+
+pub fn write_stdin_hint<O: Write + WriteColor>(output: &mut O, hint: &str) -> Result<()> {
+    write_hint(
+        output,
+        &format!("{} Press `{}` to end the input.", hint, EOF_KEYS),
+    )
+}
+
 pub struct Splitter<I: BufRead> {
     input: I,
     terminator: Terminator,
+    invalid_utf8: InvalidUtf8,
     buffer: Vec<u8>,
+    value: String,
 }
 
 impl<I: BufRead> Splitter<I> {
-    pub fn new(input: I, terminator: Terminator) -> Self {
+    pub fn new(input: I, terminator: Terminator, invalid_utf8: InvalidUtf8) -> Self {
         Self {
             input,
             terminator,
+            invalid_utf8,
             buffer: Vec::new(),
+            value: String::new(),
         }
     }
 
     pub fn read(&mut self) -> Result<Option<(&str, usize)>> {
+        loop {
+            // Reuse allocation of the previous value.
+            self.buffer = mem::take(&mut self.value).into_bytes();
+
+            let (size, orig_size) = match self.read_bytes()? {
+                Some(sizes) => sizes,
+                None => return Ok(None),
+            };
+
+            let mut data = mem::take(&mut self.buffer);
+            data.truncate(size);
+
+            self.value = match String::from_utf8(data) {
+                Ok(value) if self.invalid_utf8 == InvalidUtf8::Escape && value.contains('\\') => {
+                    escape_bytes(value.as_bytes())
+                }
+                Ok(value) => value,
+                Err(error) => match self.invalid_utf8 {
+                    InvalidUtf8::Error => return Err(make_utf8_error(error.utf8_error())),
+                    InvalidUtf8::Lossy => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+                    InvalidUtf8::Escape => escape_bytes(error.as_bytes()),
+                    InvalidUtf8::Skip => continue,
+                },
+            };
+
+            return Ok(Some((&self.value, orig_size)));
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<Option<(usize, usize)>> {
         self.buffer.clear();
 
         let mut size = match self.terminator {
@@ -59,7 +138,7 @@ impl<I: BufRead> Splitter<I> {
             };
 
             if valid {
-                return str_from_utf8(&self.buffer[..size]).map(|str| Some((str, orig_size)));
+                return Ok(Some((size, orig_size)));
             }
         }
 
@@ -69,10 +148,13 @@ impl<I: BufRead> Splitter<I> {
 
 #[cfg(test)]
 mod tests {
+    use std::io::ErrorKind;
+
+    use termcolor::Color;
     use test_case::test_case;
 
     use super::*;
-    use crate::testing::unpack_io_error;
+    use crate::testing::{unpack_io_error, ColoredOuput, OutputChunk};
 
     const NONE: Terminator = Terminator::None;
     const NL_REQ: Terminator = Terminator::Newline { required: true };
@@ -147,10 +229,64 @@ mod tests {
     #[test_case(NONE,   "abc\n\0def",         0, Some(("abc\n\0def", 8)) ; "none nonempty 0")]
     #[test_case(NONE,   "abc\n\0def",         1, None                    ; "none nonempty 1")]
     fn read(terminator: Terminator, input: &str, position: usize, result: Option<(&str, usize)>) {
-        let mut splitter = Splitter::new(input.as_bytes(), terminator);
+        let mut splitter = Splitter::new(input.as_bytes(), terminator, InvalidUtf8::Error);
         for _ in 0..position {
             splitter.read().unwrap_or_default();
         }
         assert_eq!(splitter.read().map_err(unpack_io_error), Ok(result));
     }
+
+    #[test_case(InvalidUtf8::Lossy,  Some(("a\u{FFFD}b", 4)) ; "lossy")]
+    #[test_case(InvalidUtf8::Escape, Some(("a\\xFFb", 4))   ; "escape")]
+    #[test_case(InvalidUtf8::Skip,   Some(("c", 1))          ; "skip")]
+    fn read_invalid_utf8(invalid_utf8: InvalidUtf8, result: Option<(&str, usize)>) {
+        let mut splitter = Splitter::new(&b"a\xFFb\nc"[..], NL_OPT, invalid_utf8);
+        assert_eq!(splitter.read().map_err(unpack_io_error), Ok(result));
+    }
+
+    #[test]
+    fn read_escape_backslash() {
+        let mut splitter = Splitter::new(&b"a\\b\n"[..], NL_OPT, InvalidUtf8::Escape);
+        assert_eq!(
+            splitter.read().map_err(unpack_io_error),
+            Ok(Some(("a\\\\b", 4)))
+        );
+    }
+
+    #[test]
+    fn read_invalid_utf8_error() {
+        let mut splitter = Splitter::new(&b"a\xFFb\nc"[..], NL_OPT, InvalidUtf8::Error);
+        assert_eq!(
+            splitter.read().map_err(unpack_io_error),
+            Err((
+                ErrorKind::InvalidData,
+                "Value does not have UTF-8 encoding (offset 1)".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn write_stdin_hint() {
+        let mut output = ColoredOuput::new();
+        super::write_stdin_hint(&mut output, "Reading.").unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[
+                OutputChunk::color(Color::Yellow, "hint:"),
+                OutputChunk::plain(" Reading. Press "),
+                OutputChunk::color(Color::Green, EOF_KEYS),
+                OutputChunk::plain(" to end the input.\n")
+            ]
+        );
+    }
+
+    #[test_case(ERROR,  Ok(InvalidUtf8::Error)  ; "error")]
+    #[test_case(LOSSY,  Ok(InvalidUtf8::Lossy)  ; "lossy")]
+    #[test_case(ESCAPE, Ok(InvalidUtf8::Escape) ; "escape")]
+    #[test_case(SKIP,   Ok(InvalidUtf8::Skip)   ; "skip")]
+    #[test_case("x",    Err("invalid value")    ; "invalid")]
+    fn parse_invalid_utf8(value: &str, result: std::result::Result<InvalidUtf8, &str>) {
+        assert_eq!(super::parse_invalid_utf8(value), result);
+    }
 }
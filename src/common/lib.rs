@@ -1,4 +1,5 @@
 pub mod color;
+pub mod file;
 pub mod help;
 pub mod input;
 pub mod output;
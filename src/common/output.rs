@@ -4,6 +4,7 @@ use std::io::{Result, Write};
 use termcolor::{Color, WriteColor};
 
 use crate::color::spec_color;
+use crate::help::highlight;
 
 pub fn write_error<O: Write + WriteColor, E: Error>(output: &mut O, error: &E) -> Result<()> {
     output.set_color(&spec_color(Color::Red))?;
@@ -12,6 +13,14 @@ pub fn write_error<O: Write + WriteColor, E: Error>(output: &mut O, error: &E) -
     writeln!(output, " {}", error)
 }
 
+pub fn write_hint<O: Write + WriteColor>(output: &mut O, hint: &str) -> Result<()> {
+    output.set_color(&spec_color(Color::Yellow))?;
+    write!(output, "hint:")?;
+    output.reset()?;
+    write!(output, " ")?;
+    highlight(output, hint)
+}
+
 #[cfg(test)]
 pub mod tests {
     use std::io::{self, ErrorKind};
@@ -33,4 +42,20 @@ pub mod tests {
             ]
         );
     }
+
+    #[test]
+    fn write_hint() {
+        let mut output = ColoredOuput::new();
+        super::write_hint(&mut output, "Use `abc`").unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[
+                OutputChunk::color(Color::Yellow, "hint:"),
+                OutputChunk::plain(" Use "),
+                OutputChunk::color(Color::Green, "abc"),
+                OutputChunk::plain("\n")
+            ]
+        );
+    }
 }
@@ -10,6 +10,9 @@ use crate::output::write_error;
 pub const EXIT_CODE_OK: i32 = 0;
 pub const EXIT_CODE_IO_ERROR: i32 = 1;
 pub const EXIT_CODE_CLI_ERROR: i32 = 2;
+pub const EXIT_CODE_PARSE_ERROR: i32 = 3;
+pub const EXIT_CODE_EVAL_ERROR: i32 = 4;
+pub const EXIT_CODE_CHECK_FAILURE: i32 = 5;
 
 pub type Result = io::Result<i32>;
 
@@ -32,15 +35,15 @@ impl Io {
         }
     }
 
-    pub fn stdin(&self) -> StdinLock {
+    pub fn stdin(&self) -> StdinLock<'_> {
         self.stdin.lock()
     }
 
-    pub fn stdout(&self) -> StandardStreamLock {
+    pub fn stdout(&self) -> StandardStreamLock<'_> {
         self.stdout.lock()
     }
 
-    pub fn stderr(&self) -> StandardStreamLock {
+    pub fn stderr(&self) -> StandardStreamLock<'_> {
         self.stderr.lock()
     }
 }
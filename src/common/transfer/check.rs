@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use same_file::is_same_file;
+
+use crate::transfer::fs::{FileType, TransferMode};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Status {
+    Applicable,
+    Applied,
+    Conflict,
+    Missing,
+    Collision,
+}
+
+impl Status {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Applicable | Self::Applied)
+    }
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct Summary {
+    pub applicable: usize,
+    pub applied: usize,
+    pub conflict: usize,
+    pub missing: usize,
+    pub collision: usize,
+}
+
+impl Summary {
+    pub fn add(&mut self, status: Status) {
+        match status {
+            Status::Applicable => self.applicable += 1,
+            Status::Applied => self.applied += 1,
+            Status::Conflict => self.conflict += 1,
+            Status::Missing => self.missing += 1,
+            Status::Collision => self.collision += 1,
+        }
+    }
+}
+
+// Mirrors what transfer_path would do for the given mode
+pub fn check_transfer(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Status {
+    match (FileType::from(src_path), FileType::from(dst_path)) {
+        (FileType::Unknown, FileType::Unknown) => Status::Missing,
+        (FileType::Unknown, _) if mode == TransferMode::Move => Status::Applied,
+        (FileType::Unknown, _) => Status::Missing,
+        (FileType::Dir, _) if mode == TransferMode::Hardlink => Status::Conflict,
+        (_, dst_type) if matches!(mode, TransferMode::Symlink { .. } | TransferMode::Hardlink) => {
+            if dst_type != FileType::Unknown && is_same_file(src_path, dst_path).unwrap_or(false) {
+                Status::Applied
+            } else if dst_path
+                .symlink_metadata()
+                .is_ok_and(|metadata| metadata.is_dir())
+            {
+                Status::Conflict // Only a real directory is not replaced by a link
+            } else {
+                Status::Applicable
+            }
+        }
+        (_, FileType::Unknown) => Status::Applicable,
+        (FileType::File, FileType::Dir) | (FileType::Dir, FileType::File) => Status::Conflict,
+        _ => {
+            if is_same_file(src_path, dst_path).unwrap_or(false) {
+                Status::Applied
+            } else {
+                Status::Applicable // File is overwritten, directory is merged
+            }
+        }
+    }
+}
+
+// Destination paths differing only in letter case would overwrite each other on a case-insensitive FS
+pub fn mark_case_collisions(results: &mut [(Status, PathBuf, PathBuf)]) {
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for (index, (_, _, dst_path)) in results.iter().enumerate() {
+        let folded_path = dst_path.to_string_lossy().to_lowercase();
+        groups.entry(folded_path).or_default().push(index);
+    }
+
+    for indices in groups.values() {
+        let first_path = &results[indices[0]].2;
+
+        if indices.iter().any(|&index| results[index].2 != *first_path) {
+            for &index in indices {
+                if results[index].0.is_ok() {
+                    results[index].0 = Status::Collision;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::transfer::fs::Reflink;
+
+    const M: TransferMode = TransferMode::Move;
+    const C: TransferMode = TransferMode::Copy {
+        reflink: Reflink::Auto,
+    };
+    const S: TransferMode = TransferMode::Symlink { relative: false };
+    const H: TransferMode = TransferMode::Hardlink;
+
+    #[test_case("file", "none", M, Status::Applicable ; "move to missing")]
+    #[test_case("file", "none", C, Status::Applicable ; "copy to missing")]
+    #[test_case("dir",  "none", M, Status::Applicable ; "move dir to missing")]
+    #[test_case("none", "file", M, Status::Applied    ; "move already done")]
+    #[test_case("none", "file", C, Status::Missing    ; "copy from missing")]
+    #[test_case("none", "none", M, Status::Missing    ; "move from missing")]
+    #[test_case("file", "file", M, Status::Applied    ; "move to itself")]
+    #[test_case("file", "othr", M, Status::Applicable ; "move to existing")]
+    #[test_case("file", "othr", C, Status::Applicable ; "copy to existing")]
+    #[test_case("dir",  "odir", C, Status::Applicable ; "copy dir to existing dir")]
+    #[test_case("file", "dir",  M, Status::Conflict   ; "move file to dir")]
+    #[test_case("dir",  "othr", M, Status::Conflict   ; "move dir to file")]
+    #[test_case("file", "none", S, Status::Applicable ; "symlink to missing")]
+    #[test_case("file", "othr", S, Status::Applicable ; "symlink to existing")]
+    #[test_case("dir",  "othr", S, Status::Applicable ; "symlink dir to existing")]
+    #[test_case("file", "file", S, Status::Applied    ; "symlink to itself")]
+    #[test_case("file", "dir",  S, Status::Conflict   ; "symlink to dir")]
+    #[test_case("file", "othr", H, Status::Applicable ; "hardlink to existing")]
+    #[test_case("dir",  "none", H, Status::Conflict   ; "hardlink dir")]
+    fn check_transfer(src: &str, dst: &str, mode: TransferMode, status: Status) {
+        let dir = TempDir::new().unwrap();
+        dir.child("file").touch().unwrap();
+        dir.child("othr").touch().unwrap();
+        dir.child("dir").create_dir_all().unwrap();
+        dir.child("odir").create_dir_all().unwrap();
+
+        assert_eq!(
+            super::check_transfer(dir.child(src).path(), dir.child(dst).path(), mode),
+            status
+        );
+    }
+
+    #[test]
+    fn mark_case_collisions() {
+        let mut results = [
+            (Status::Applicable, "a".into(), "x/File".into()),
+            (Status::Applicable, "b".into(), "y".into()),
+            (Status::Applied, "c".into(), "x/file".into()),
+            (Status::Missing, "d".into(), "X/FILE".into()),
+            (Status::Applicable, "e".into(), "z".into()),
+            (Status::Applicable, "f".into(), "z".into()),
+        ];
+
+        super::mark_case_collisions(&mut results);
+
+        assert_eq!(
+            results.map(|(status, _, _)| status),
+            [
+                Status::Collision,
+                Status::Applicable,
+                Status::Collision,
+                Status::Missing,
+                Status::Applicable,
+                Status::Applicable,
+            ]
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let mut summary = Summary::default();
+        summary.add(Status::Applicable);
+        summary.add(Status::Applicable);
+        summary.add(Status::Applied);
+        summary.add(Status::Missing);
+        summary.add(Status::Collision);
+
+        assert_eq!(
+            summary,
+            Summary {
+                applicable: 2,
+                applied: 1,
+                conflict: 0,
+                missing: 1,
+                collision: 1,
+            }
+        );
+    }
+}
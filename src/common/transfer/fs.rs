@@ -1,11 +1,15 @@
-use std::fs;
-use std::path::Path;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
 
 use fs_extra::error::{Error, ErrorKind, Result};
 use fs_extra::{dir, file};
 use lazy_static::lazy_static;
 use same_file::is_same_file;
 
+use crate::file::make_temp_path;
+
 #[derive(PartialEq, Debug)]
 pub enum FileType {
     File,
@@ -28,10 +32,19 @@ impl From<&Path> for FileType {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Reflink {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum TransferMode {
     Move,
-    Copy,
+    Copy { reflink: Reflink },
+    Symlink { relative: bool },
+    Hardlink,
 }
 
 pub fn transfer_path(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Result<()> {
@@ -44,6 +57,11 @@ pub fn transfer_path(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Re
             ),
         )),
 
+        // Links replace anything but a real directory, so the destination is classified separately
+        (src_type, _) if matches!(mode, TransferMode::Symlink { .. } | TransferMode::Hardlink) => {
+            link_path(src_path, dst_path, src_type, mode)
+        }
+
         (FileType::File, FileType::Dir) => Err(Error::new(
             ErrorKind::Other,
             &format!(
@@ -72,11 +90,12 @@ pub fn transfer_path(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Re
                         file::move_file(src_path, dst_path, &FILE_COPY_OPTIONS)?;
                     }
                 }
-                TransferMode::Copy => {
+                TransferMode::Copy { reflink } => {
                     if dst_type == FileType::Unknown || !is_same_file(src_path, dst_path)? {
-                        file::copy(src_path, dst_path, &FILE_COPY_OPTIONS)?;
+                        copy_file(src_path, dst_path, reflink)?;
                     }
                 }
+                TransferMode::Symlink { .. } | TransferMode::Hardlink => unreachable!(),
             }
             Ok(())
         }
@@ -89,17 +108,228 @@ pub fn transfer_path(src_path: &Path, dst_path: &Path, mode: TransferMode) -> Re
                         dir::move_dir(src_path, dst_path, &DIR_COPY_OPTIONS)?;
                     }
                 }
-                TransferMode::Copy => {
+                TransferMode::Copy { reflink } => {
                     if dst_type == FileType::Unknown || !is_same_file(src_path, dst_path)? {
-                        dir::copy(src_path, dst_path, &DIR_COPY_OPTIONS)?;
+                        copy_dir(src_path, dst_path, reflink)?;
                     }
                 }
+                TransferMode::Symlink { .. } | TransferMode::Hardlink => unreachable!(),
             }
             Ok(())
         }
     }
 }
 
+fn link_path(
+    src_path: &Path,
+    dst_path: &Path,
+    src_type: FileType,
+    mode: TransferMode,
+) -> Result<()> {
+    if dst_path.exists() && is_same_file(src_path, dst_path)? {
+        return Ok(()); // Destination is already linked to source (or it is the same path)
+    }
+
+    // Does not follow symlinks, so an existing link to a directory is replaced too
+    let dst_metadata = dst_path.symlink_metadata().ok();
+
+    if dst_metadata.as_ref().is_some_and(fs::Metadata::is_dir) {
+        return Err(Error::new(
+            ErrorKind::Other,
+            &format!(
+                "Cannot overwrite directory '{}' with link to '{}'",
+                dst_path.to_string_lossy(),
+                src_path.to_string_lossy()
+            ),
+        ));
+    }
+
+    if src_type == FileType::Dir && mode == TransferMode::Hardlink {
+        return Err(Error::new(
+            ErrorKind::Other,
+            &format!(
+                "Cannot create hard link to directory '{}'",
+                src_path.to_string_lossy()
+            ),
+        ));
+    }
+
+    if let Some(dst_parent) = dst_path.parent() {
+        dir::create_all(dst_parent, false)?;
+    }
+
+    if let Some(dst_metadata) = dst_metadata {
+        remove_link_or_file(dst_path, &dst_metadata)?; // Existing file or (possibly broken) symlink
+    }
+
+    match mode {
+        TransferMode::Symlink { relative } => {
+            let src_path_resolved = resolve_path(src_path)?;
+            let target = if relative {
+                let dst_dir = resolve_path(dst_path)?;
+                let dst_dir = dst_dir.parent().unwrap_or(&dst_dir);
+                pathdiff::diff_paths(&src_path_resolved, dst_dir).unwrap_or(src_path_resolved)
+            } else {
+                src_path_resolved
+            };
+            create_symlink(&target, dst_path, src_type == FileType::Dir)?;
+        }
+        TransferMode::Hardlink => {
+            if let Err(error) = fs::hard_link(src_path, dst_path) {
+                if error.kind() == io::ErrorKind::CrossesDevices {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        &format!(
+                            "Cannot create hard link '{}' to '{}' on a different device",
+                            dst_path.to_string_lossy(),
+                            src_path.to_string_lossy()
+                        ),
+                    ));
+                }
+                return Err(error.into());
+            }
+        }
+        TransferMode::Move | TransferMode::Copy { .. } => unreachable!(),
+    }
+
+    Ok(())
+}
+
+fn copy_file(src_path: &Path, dst_path: &Path, reflink: Reflink) -> Result<()> {
+    match reflink {
+        Reflink::Auto => {
+            file::copy(src_path, dst_path, &FILE_COPY_OPTIONS)?; // std::fs::copy clones data where possible
+        }
+        Reflink::Always => {
+            // Clone to a temporary path first, so a failure does not truncate the destination
+            let temp_path = make_temp_path(dst_path);
+            let result =
+                clone_file(src_path, &temp_path).and_then(|_| fs::rename(&temp_path, dst_path));
+
+            if let Err(error) = result {
+                fs::remove_file(&temp_path).ok();
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    &format!(
+                        "Cannot create reflink '{}' to '{}': {}",
+                        dst_path.to_string_lossy(),
+                        src_path.to_string_lossy(),
+                        error
+                    ),
+                ));
+            }
+        }
+        Reflink::Never => {
+            // Plain read/write loop, because std::io::copy may use copy_file_range on Linux
+            let mut src_file = File::open(src_path)?;
+            let mut dst_file = File::create(dst_path)?;
+            let mut buffer = vec![0; FILE_COPY_OPTIONS.buffer_size];
+
+            loop {
+                match src_file.read(&mut buffer)? {
+                    0 => break,
+                    size => dst_file.write_all(&buffer[..size])?,
+                }
+            }
+
+            dst_file.set_permissions(src_file.metadata()?.permissions())?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_dir(src_path: &Path, dst_path: &Path, reflink: Reflink) -> Result<()> {
+    if reflink == Reflink::Auto {
+        dir::copy(src_path, dst_path, &DIR_COPY_OPTIONS)?;
+        return Ok(());
+    }
+
+    // Contents are merged with existing destination directory, the same way as dir::copy does
+    for entry in fs::read_dir(src_path)? {
+        let entry = entry?;
+        let src_entry_path = entry.path();
+        let dst_entry_path = dst_path.join(entry.file_name());
+
+        if src_entry_path.is_dir() {
+            dir::create_all(&dst_entry_path, false)?;
+            copy_dir(&src_entry_path, &dst_entry_path, reflink)?;
+        } else {
+            copy_file(&src_entry_path, &dst_entry_path, reflink)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn clone_file(src_path: &Path, dst_path: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    const FICLONE: u32 = 0x4004_9409; // _IOW(0x94, 9, int)
+
+    let src_file = File::open(src_path)?;
+    let dst_file = File::create(dst_path)?;
+
+    if unsafe { libc::ioctl(dst_file.as_raw_fd(), FICLONE as _, src_file.as_raw_fd()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    dst_file.set_permissions(src_file.metadata()?.permissions())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn clone_file(_src_path: &Path, _dst_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Reflinks are not supported on this platform",
+    ))
+}
+
+// Absolute path with resolved symlinks in parent directories (but not in the path itself).
+fn resolve_path(path: &Path) -> io::Result<PathBuf> {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            Ok(parent.canonicalize()?.join(name))
+        }
+        _ => path.canonicalize(),
+    }
+}
+
+#[cfg(unix)]
+fn remove_link_or_file(path: &Path, _metadata: &fs::Metadata) -> io::Result<()> {
+    fs::remove_file(path)
+}
+
+#[cfg(windows)]
+fn remove_link_or_file(path: &Path, metadata: &fs::Metadata) -> io::Result<()> {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_DIRECTORY: u32 = 0x10;
+
+    if metadata.file_attributes() & FILE_ATTRIBUTE_DIRECTORY != 0 {
+        fs::remove_dir(path) // Directory symlink or junction
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path, _dir: bool) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path, dir: bool) -> io::Result<()> {
+    if dir {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
 lazy_static! {
     pub static ref FILE_COPY_OPTIONS: file::CopyOptions = get_file_copy_options();
     pub static ref DIR_COPY_OPTIONS: dir::CopyOptions = get_dir_copy_options();
@@ -147,7 +377,7 @@ mod tests {
             let src_file = temp_file("a");
 
             assert_eq!(
-                transfer_path(src_file.path(), &Path::new("b"), TransferMode::Move) // Mode is irrelevant
+                transfer_path(src_file.path(), Path::new("b"), TransferMode::Move) // Mode is irrelevant
                     .map_err(unpack_fse_error),
                 Err((
                     debug_fse_error_kind(ErrorKind::NotFound),
@@ -254,8 +484,14 @@ mod tests {
             let dst_file = temp_file("b");
 
             assert_eq!(
-                transfer_path(src_file.path(), dst_file.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Auto
+                    }
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -269,8 +505,14 @@ mod tests {
             let src_file = write(temp_file("a"), "1");
 
             assert_eq!(
-                transfer_path(src_file.path(), src_file.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    src_file.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Auto
+                    }
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -283,8 +525,14 @@ mod tests {
             let dst_file = write(temp_file("b"), "2");
 
             assert_eq!(
-                transfer_path(src_file.path(), dst_file.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Auto
+                    }
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -364,8 +612,14 @@ mod tests {
             let dst_file = dst_dir.child("c");
 
             assert_eq!(
-                transfer_path(src_dir.path(), dst_dir.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Auto
+                    }
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -383,8 +637,14 @@ mod tests {
             let src_file = write(src_dir.child("a"), "1");
 
             assert_eq!(
-                transfer_path(src_dir.path(), src_dir.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    src_dir.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Auto
+                    }
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -403,8 +663,14 @@ mod tests {
             let dst_file = write(dst_dir.child("c"), "2");
 
             assert_eq!(
-                transfer_path(src_dir.path(), dst_dir.path(), TransferMode::Copy)
-                    .map_err(unpack_fse_error),
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Auto
+                    }
+                )
+                .map_err(unpack_fse_error),
                 Ok(())
             );
 
@@ -414,6 +680,320 @@ mod tests {
             dst_dir.assert(predicates::path::is_dir());
             dst_file.assert("1");
         }
+
+        #[test]
+        fn copy_file_without_reflink() {
+            let src_file = write(temp_file("a"), "1");
+            let dst_file = write(temp_file("b"), "22");
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Never
+                    }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_file.assert("1");
+            dst_file.assert("1");
+        }
+
+        #[test]
+        fn copy_dir_without_reflink() {
+            let root_dir = temp_dir();
+
+            let src_dir = mkdir(root_dir.child("a"));
+            write(src_dir.child("c"), "1");
+            write(src_dir.child("d/e"), "2");
+
+            let dst_dir = mkdir(root_dir.child("b"));
+            write(dst_dir.child("c"), "3");
+            write(dst_dir.child("f"), "4");
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Copy {
+                        reflink: Reflink::Never
+                    }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_dir.child("c").assert("1");
+            dst_dir.child("c").assert("1");
+            dst_dir.child("d/e").assert("2");
+            dst_dir.child("f").assert("4");
+        }
+
+        #[test]
+        fn copy_file_with_reflink() {
+            let root_dir = temp_dir();
+            let src_file = write(root_dir.child("a"), "1");
+            let dst_file = write(root_dir.child("b"), "2");
+
+            // Result depends on file system support
+            match transfer_path(
+                src_file.path(),
+                dst_file.path(),
+                TransferMode::Copy {
+                    reflink: Reflink::Always,
+                },
+            ) {
+                Ok(()) => {
+                    dst_file.assert("1");
+                }
+                Err(error) => {
+                    assert!(error.to_string().starts_with(&format!(
+                        "Cannot create reflink '{}' to '{}': ",
+                        dst_file.path().to_string_lossy(),
+                        src_file.path().to_string_lossy()
+                    )));
+                    dst_file.assert("2");
+                }
+            }
+
+            src_file.assert("1");
+            assert_eq!(fs::read_dir(root_dir.path()).unwrap().count(), 2); // No temporary file left
+        }
+
+        #[test]
+        fn symlink_file() {
+            let root_dir = temp_dir();
+            let src_file = write(root_dir.child("a"), "1");
+            let dst_file = root_dir.child("b/c");
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Symlink { relative: false }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_file.assert("1");
+            dst_file.assert("1");
+            assert!(fs::read_link(dst_file.path()).unwrap().is_absolute());
+        }
+
+        #[test]
+        fn symlink_file_relative() {
+            let root_dir = temp_dir();
+            let src_file = write(root_dir.child("a"), "1");
+            let dst_file = root_dir.child("b/c");
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Symlink { relative: true }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            dst_file.assert("1");
+            assert_eq!(
+                fs::read_link(dst_file.path()).unwrap(),
+                Path::new("..").join("a")
+            );
+        }
+
+        #[test]
+        fn symlink_dir() {
+            let root_dir = temp_dir();
+            let src_dir = mkdir(root_dir.child("a"));
+            write(src_dir.child("c"), "1");
+            let dst_dir = root_dir.child("b");
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Symlink { relative: true }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            dst_dir.child("c").assert("1");
+            assert_eq!(fs::read_link(dst_dir.path()).unwrap(), Path::new("a"));
+        }
+
+        #[test]
+        fn symlink_to_itself() {
+            let src_file = write(temp_file("a"), "1");
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    src_file.path(),
+                    TransferMode::Symlink { relative: false }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_file.assert("1");
+            assert!(fs::read_link(src_file.path()).is_err());
+        }
+
+        #[test]
+        fn symlink_replaces_file() {
+            let root_dir = temp_dir();
+            let src_file = write(root_dir.child("a"), "1");
+            let dst_file = write(root_dir.child("b"), "2");
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    dst_file.path(),
+                    TransferMode::Symlink { relative: true }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_file.assert("1");
+            dst_file.assert("1");
+        }
+
+        #[test]
+        fn symlink_overwrite_dir() {
+            let src_file = touch(temp_file("a"));
+            let dst_dir = temp_dir();
+
+            assert_eq!(
+                transfer_path(
+                    src_file.path(),
+                    dst_dir.path(),
+                    TransferMode::Symlink { relative: false }
+                )
+                .map_err(unpack_fse_error),
+                Err((
+                    debug_fse_error_kind(ErrorKind::Other),
+                    format!(
+                        "Cannot overwrite directory '{}' with link to '{}'",
+                        dst_dir.path().to_string_lossy(),
+                        src_file.path().to_string_lossy()
+                    ),
+                ))
+            );
+
+            dst_dir.assert(predicates::path::is_dir());
+        }
+
+        #[test]
+        fn symlink_dir_replaces_file() {
+            let root_dir = temp_dir();
+            let src_dir = mkdir(root_dir.child("a"));
+            let dst_file = write(root_dir.child("b"), "2");
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_file.path(),
+                    TransferMode::Symlink { relative: true }
+                )
+                .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            assert!(dst_file.path().is_dir());
+            assert_eq!(fs::read_link(dst_file.path()).unwrap(), Path::new("a"));
+        }
+
+        #[test]
+        fn symlink_replaces_dir_symlink() {
+            let root_dir = temp_dir();
+            let old_src_dir = mkdir(root_dir.child("a"));
+            let src_file = write(root_dir.child("b"), "1");
+            let dst_path = root_dir.child("c");
+            let mode = TransferMode::Symlink { relative: true };
+
+            transfer_path(old_src_dir.path(), dst_path.path(), mode).unwrap();
+
+            assert_eq!(
+                transfer_path(src_file.path(), dst_path.path(), mode).map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            old_src_dir.assert(predicates::path::is_dir());
+            dst_path.assert("1");
+            assert_eq!(fs::read_link(dst_path.path()).unwrap(), Path::new("b"));
+        }
+
+        #[test]
+        fn symlink_dir_overwrite_dir() {
+            let src_dir = temp_dir();
+            let dst_dir = temp_dir();
+
+            assert_eq!(
+                transfer_path(
+                    src_dir.path(),
+                    dst_dir.path(),
+                    TransferMode::Symlink { relative: false }
+                )
+                .map_err(unpack_fse_error),
+                Err((
+                    debug_fse_error_kind(ErrorKind::Other),
+                    format!(
+                        "Cannot overwrite directory '{}' with link to '{}'",
+                        dst_dir.path().to_string_lossy(),
+                        src_dir.path().to_string_lossy()
+                    ),
+                ))
+            );
+
+            dst_dir.assert(predicates::path::is_dir());
+        }
+
+        #[test]
+        fn hardlink_file() {
+            let root_dir = temp_dir();
+            let src_file = write(root_dir.child("a"), "1");
+            let dst_file = write(root_dir.child("b"), "2");
+
+            assert_eq!(
+                transfer_path(src_file.path(), dst_file.path(), TransferMode::Hardlink)
+                    .map_err(unpack_fse_error),
+                Ok(())
+            );
+
+            src_file.assert("1");
+            dst_file.assert("1");
+            assert!(is_same_file(src_file.path(), dst_file.path()).unwrap());
+            assert!(fs::read_link(dst_file.path()).is_err());
+        }
+
+        #[test]
+        fn hardlink_dir() {
+            let src_dir = temp_dir();
+            let dst_dir = temp_dir();
+            let dst_path = dst_dir.child("b");
+
+            assert_eq!(
+                transfer_path(src_dir.path(), dst_path.path(), TransferMode::Hardlink)
+                    .map_err(unpack_fse_error),
+                Err((
+                    debug_fse_error_kind(ErrorKind::Other),
+                    format!(
+                        "Cannot create hard link to directory '{}'",
+                        src_dir.path().to_string_lossy()
+                    ),
+                ))
+            );
+
+            dst_path.assert(predicates::path::missing());
+        }
     }
 
     #[test]
@@ -2,8 +2,11 @@ use std::fmt;
 use std::io::{BufRead, Error, ErrorKind, Result};
 use std::path::PathBuf;
 
-use crate::input::{Splitter, Terminator};
+use crate::input::{InvalidUtf8, Splitter, Terminator};
 use crate::symbols::{DIFF_IN, DIFF_OUT};
+#[cfg(not(unix))]
+use crate::utils::make_utf8_error;
+use crate::utils::unescape_bytes;
 
 struct Position {
     item: usize,
@@ -30,25 +33,33 @@ impl fmt::Display for Position {
 pub struct PathDiff<I: BufRead> {
     splitter: Splitter<I>,
     position: Position,
+    unescape: bool,
 }
 
 impl<I: BufRead> PathDiff<I> {
-    pub fn new(input: I, terminator: Terminator) -> Self {
+    pub fn new(input: I, terminator: Terminator, unescape: bool) -> Self {
         Self {
-            splitter: Splitter::new(input, terminator),
+            splitter: Splitter::new(input, terminator, InvalidUtf8::Error),
             position: Position::new(),
+            unescape,
         }
     }
 
     pub fn read(&mut self) -> Result<Option<(PathBuf, PathBuf)>> {
         let (in_path, in_size) = match self.splitter.read()? {
-            Some((value, size)) => (extract_path(value, &self.position, DIFF_IN)?, size),
+            Some((value, size)) => (
+                extract_path(value, &self.position, DIFF_IN, self.unescape)?,
+                size,
+            ),
             None => return Ok(None),
         };
         self.position.increment(in_size);
 
         let (out_path, out_size) = match self.splitter.read()? {
-            Some((value, size)) => (extract_path(value, &self.position, DIFF_OUT)?, size),
+            Some((value, size)) => (
+                extract_path(value, &self.position, DIFF_OUT, self.unescape)?,
+                size,
+            ),
             None => return Err(make_unexpected_eof_error(&self.position, DIFF_OUT)),
         };
         self.position.increment(out_size);
@@ -57,7 +68,7 @@ impl<I: BufRead> PathDiff<I> {
     }
 }
 
-fn extract_path(value: &str, position: &Position, prefix: char) -> Result<PathBuf> {
+fn extract_path(value: &str, position: &Position, prefix: char, unescape: bool) -> Result<PathBuf> {
     if let Some(first_char) = value.chars().next() {
         if first_char == prefix {
             let path = &value[prefix.len_utf8()..];
@@ -66,6 +77,8 @@ fn extract_path(value: &str, position: &Position, prefix: char) -> Result<PathBu
                     ErrorKind::UnexpectedEof,
                     format!("Expected a path after '{}' ({})", prefix, position),
                 ))
+            } else if unescape {
+                path_from_bytes(unescape_bytes(path)?)
             } else {
                 Ok(path.into())
             }
@@ -83,6 +96,20 @@ fn extract_path(value: &str, position: &Position, prefix: char) -> Result<PathBu
     }
 }
 
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> Result<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+    Ok(OsString::from_vec(bytes).into())
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> Result<PathBuf> {
+    String::from_utf8(bytes)
+        .map(PathBuf::from)
+        .map_err(|error| make_utf8_error(error.utf8_error()))
+}
+
 fn make_unexpected_eof_error(position: &Position, prefix: char) -> Error {
     Error::new(
         ErrorKind::UnexpectedEof,
@@ -115,8 +142,11 @@ mod tests {
         #[test_case("<abc\n>def\n< g \n> h ", 1, Some((" g ", " h ")) ; "nonempty 1")]
         #[test_case("<abc\n>def\n< g \n> h ", 2, None                 ; "nonempty 2")]
         fn ok(input: &str, position: usize, result: Option<(&str, &str)>) {
-            let mut path_diff =
-                PathDiff::new(input.as_bytes(), Terminator::Newline { required: false });
+            let mut path_diff = PathDiff::new(
+                input.as_bytes(),
+                Terminator::Newline { required: false },
+                false,
+            );
 
             for _ in 0..position {
                 path_diff.read().unwrap_or_default();
@@ -128,6 +158,40 @@ mod tests {
             );
         }
 
+        #[test_case("<a\\\\b\n>c\\x41\n", ("a\\b", "cA") ; "valid")]
+        #[cfg_attr(unix, test_case("<a\\xFF\n>b\n", ("a\u{FFFD}", "b") ; "invalid utf8"))]
+        fn unescape(input: &str, result: (&str, &str)) {
+            let (src_path, dst_path) = PathDiff::new(
+                input.as_bytes(),
+                Terminator::Newline { required: false },
+                true,
+            )
+            .read()
+            .unwrap()
+            .unwrap();
+
+            assert_eq!(src_path.to_string_lossy(), result.0);
+            assert_eq!(dst_path.to_string_lossy(), result.1);
+        }
+
+        #[test]
+        fn unescape_err() {
+            assert_eq!(
+                PathDiff::new(
+                    &b"<a\\b\n>c\n"[..],
+                    Terminator::Newline { required: false },
+                    true
+                )
+                .read()
+                .map_err(unpack_io_error),
+                Err((
+                    E::InvalidData,
+                    "Value 'a\\b' contains invalid escape sequence (expected '\\\\' or '\\xNN')"
+                        .into()
+                ))
+            );
+        }
+
         type E = ErrorKind;
 
         #[test_case("a",     E::InvalidData,   "Expected '<' but got 'a' (item #1 at offset 0)"  ; "in prefix invalid")]
@@ -138,9 +202,13 @@ mod tests {
         #[test_case("<a\n>", E::UnexpectedEof, "Expected a path after '>' (item #2 at offset 3)" ; "out path missing")]
         fn err(input: &str, kind: ErrorKind, message: &str) {
             assert_eq!(
-                PathDiff::new(input.as_bytes(), Terminator::Newline { required: false })
-                    .read()
-                    .map_err(unpack_io_error),
+                PathDiff::new(
+                    input.as_bytes(),
+                    Terminator::Newline { required: false },
+                    false
+                )
+                .read()
+                .map_err(unpack_io_error),
                 Err((kind, message.into()))
             )
         }
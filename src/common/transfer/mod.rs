@@ -1,9 +1,13 @@
-pub use fs::TransferMode;
+pub use fs::{Reflink, TransferMode};
 pub use run::{run_transfer, TransferOptions};
+pub use stats::{parse_summary_format, SummaryFormat, SUMMARY_CHOICES};
 
+mod check;
 mod fs;
 mod input;
+mod order;
 mod output;
 mod run;
+mod stats;
 #[cfg(test)]
 mod testing;
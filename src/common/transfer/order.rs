@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::transfer::fs::TransferMode;
+
+#[derive(Debug, PartialEq)]
+pub struct Transfer {
+    pub src_path: PathBuf,
+    pub dst_path: PathBuf,
+    pub mode: TransferMode,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Pending,
+    Visiting,
+    Done,
+}
+
+pub fn order_transfers<T>(
+    paths: Vec<(PathBuf, PathBuf)>,
+    mode: TransferMode,
+    mut temp_path: T,
+) -> Vec<Transfer>
+where
+    T: FnMut(&Path) -> PathBuf,
+{
+    // Transfer writing to a path has to wait for all transfers reading from that path.
+    let mut readers = HashMap::<&Path, Vec<usize>>::new();
+
+    for (index, (src_path, _)) in paths.iter().enumerate() {
+        readers.entry(src_path.as_path()).or_default().push(index);
+    }
+
+    // Paths which were moved aside to resolve a cycle, their readers use the temporary path instead.
+    let mut temp_paths = HashMap::<PathBuf, PathBuf>::new();
+    let mut states = vec![State::Pending; paths.len()];
+    let mut order = Vec::with_capacity(paths.len());
+
+    for first in 0..paths.len() {
+        if states[first] != State::Pending {
+            continue;
+        }
+
+        // Each item is a transfer index and position of its next blocker to visit.
+        let mut stack = vec![(first, 0)];
+        states[first] = State::Visiting;
+
+        while let Some((index, position)) = stack.pop() {
+            let dst_path = paths[index].1.as_path();
+
+            let blocker = readers
+                .get(dst_path)
+                .filter(|_| !temp_paths.contains_key(dst_path))
+                .and_then(|indices| {
+                    (position..indices.len())
+                        .find(|position| indices[*position] != index)
+                        .map(|position| (indices[position], position + 1))
+                });
+
+            match blocker {
+                Some((blocker, next_position)) => {
+                    stack.push((index, next_position));
+
+                    match states[blocker] {
+                        State::Pending => {
+                            states[blocker] = State::Visiting;
+                            stack.push((blocker, 0));
+                        }
+                        State::Visiting => {
+                            // Cycle, move the path aside, so its readers no longer block writers.
+                            let path_temp = temp_path(dst_path);
+
+                            order.push(Transfer {
+                                src_path: dst_path.to_path_buf(),
+                                dst_path: path_temp.clone(),
+                                mode: TransferMode::Move,
+                            });
+
+                            temp_paths.insert(dst_path.to_path_buf(), path_temp);
+                        }
+                        State::Done => {}
+                    }
+                }
+                None => {
+                    let (src_path, dst_path) = &paths[index];
+
+                    order.push(Transfer {
+                        src_path: temp_paths.get(src_path).unwrap_or(src_path).clone(),
+                        dst_path: dst_path.clone(),
+                        mode,
+                    });
+
+                    states[index] = State::Done;
+                }
+            }
+        }
+    }
+
+    // The last reader of a temporary path moves it, so nothing is left behind.
+    for path_temp in temp_paths.values() {
+        if let Some(transfer) = order
+            .iter_mut()
+            .rev()
+            .find(|transfer| transfer.src_path == *path_temp)
+        {
+            transfer.mode = TransferMode::Move;
+        }
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::transfer::fs::Reflink;
+
+    const M: TransferMode = TransferMode::Move;
+    const C: TransferMode = TransferMode::Copy {
+        reflink: Reflink::Auto,
+    };
+
+    #[test_case(&[],                                     M, &[]                                                           ; "empty")]
+    #[test_case(&[("a", "b")],                           M, &[("a", "b", M)]                                              ; "single")]
+    #[test_case(&[("a", "a")],                           M, &[("a", "a", M)]                                              ; "itself")]
+    #[test_case(&[("a", "b"), ("c", "d")],               M, &[("a", "b", M), ("c", "d", M)]                               ; "independent")]
+    #[test_case(&[("a", "b"), ("b", "c")],               M, &[("b", "c", M), ("a", "b", M)]                               ; "chain")]
+    #[test_case(&[("a", "b"), ("b", "c"), ("c", "d")],   M, &[("c", "d", M), ("b", "c", M), ("a", "b", M)]                ; "long chain")]
+    #[test_case(&[("b", "c"), ("a", "b")],               M, &[("b", "c", M), ("a", "b", M)]                               ; "ordered chain")]
+    #[test_case(&[("a", "b"), ("b", "a")],               M, &[("a", "a~", M), ("b", "a", M), ("a~", "b", M)]              ; "swap")]
+    #[test_case(&[("a", "b"), ("b", "c"), ("c", "a")],   M, &[("a", "a~", M), ("c", "a", M), ("b", "c", M), ("a~", "b", M)] ; "cycle")]
+    #[test_case(&[("x", "a"), ("a", "b"), ("b", "a")],   M, &[("a", "a~", M), ("b", "a", M), ("a~", "b", M), ("x", "a", M)] ; "chain into cycle")]
+    #[test_case(&[("a", "b"), ("b", "c")],               C, &[("b", "c", C), ("a", "b", C)]                               ; "copy chain")]
+    #[test_case(&[("a", "b"), ("b", "a")],               C, &[("a", "a~", M), ("b", "a", C), ("a~", "b", M)]              ; "copy swap")]
+    #[test_case(&[("c", "a"), ("a", "b"), ("a", "d")],   C, &[("a", "b", C), ("a", "d", C), ("c", "a", C)]                ; "copy multiple readers")]
+    #[test_case(&[("a", "b"), ("a", "c"), ("b", "a")],   C, &[("a", "a~", M), ("b", "a", C), ("a~", "b", C), ("a~", "c", M)] ; "copy multiple readers cycle")]
+    fn order_transfers(
+        paths: &[(&str, &str)],
+        mode: TransferMode,
+        result: &[(&str, &str, TransferMode)],
+    ) {
+        let paths = paths
+            .iter()
+            .map(|(src, dst)| (PathBuf::from(src), PathBuf::from(dst)))
+            .collect();
+
+        let result = result
+            .iter()
+            .map(|(src, dst, mode)| Transfer {
+                src_path: src.into(),
+                dst_path: dst.into(),
+                mode: *mode,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            super::order_transfers(paths, mode, |path| {
+                PathBuf::from(format!("{}~", path.to_string_lossy()))
+            }),
+            result
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use std::io::{Result, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use termcolor::{Color, WriteColor};
 
 use crate::color::spec_color;
+use crate::transfer::check::{Status, Summary};
 use crate::transfer::fs::TransferMode;
+use crate::transfer::stats::{Stats, SummaryFormat};
+use crate::utils::escape_json;
 
 pub struct TransferLog<O: Write + WriteColor> {
     output: O,
@@ -23,7 +26,9 @@ impl<O: Write + WriteColor> TransferLog<O> {
     ) -> Result<()> {
         let action = match mode {
             TransferMode::Move => "Moving",
-            TransferMode::Copy => "Copying",
+            TransferMode::Copy { .. } => "Copying",
+            TransferMode::Symlink { .. } => "Symlinking",
+            TransferMode::Hardlink => "Hardlinking",
         };
         write!(self.output, "{} '", action)?;
         self.output.set_color(&spec_color(Color::Blue))?;
@@ -51,22 +56,137 @@ impl<O: Write + WriteColor> TransferLog<O> {
         self.output.reset()?;
         writeln!(self.output)
     }
+
+    pub fn check_result(&mut self, status: Status, src_path: &Path, dst_path: &Path) -> Result<()> {
+        let (color, label) = match status {
+            Status::Applicable => (Color::Green, "applicable"),
+            Status::Applied => (Color::Cyan, "already applied"),
+            Status::Conflict => (Color::Yellow, "conflict"),
+            Status::Missing => (Color::Red, "source missing"),
+            Status::Collision => (Color::Magenta, "case collision"),
+        };
+        self.output.set_color(&spec_color(color))?;
+        write!(self.output, "{}:", label)?;
+        self.output.reset()?;
+        write!(self.output, " '")?;
+        self.output.set_color(&spec_color(Color::Blue))?;
+        write!(self.output, "{}", src_path.to_string_lossy())?;
+        self.output.reset()?;
+        write!(self.output, "' -> '")?;
+        self.output.set_color(&spec_color(Color::Blue))?;
+        write!(self.output, "{}", dst_path.to_string_lossy())?;
+        self.output.reset()?;
+        writeln!(self.output, "'")
+    }
+
+    pub fn check_summary(&mut self, summary: &Summary) -> Result<()> {
+        writeln!(
+            self.output,
+            "{} applicable, {} already applied, {} conflict(s), {} source(s) missing, {} case collision(s)",
+            summary.applicable,
+            summary.applied,
+            summary.conflict,
+            summary.missing,
+            summary.collision
+        )
+    }
+
+    pub fn check_summary_json(
+        &mut self,
+        results: &[(Status, PathBuf, PathBuf)],
+        summary: &Summary,
+    ) -> Result<()> {
+        let results = results
+            .iter()
+            .map(|(status, src_path, dst_path)| {
+                format!(
+                    r#"{{"status":"{}","src":"{}","dst":"{}"}}"#,
+                    status_name(*status),
+                    escape_json(&src_path.to_string_lossy()),
+                    escape_json(&dst_path.to_string_lossy())
+                )
+            })
+            .collect::<Vec<_>>();
+        writeln!(
+            self.output,
+            r#"{{"results":[{}],"applicable":{},"applied":{},"conflict":{},"missing":{},"collision":{}}}"#,
+            results.join(","),
+            summary.applicable,
+            summary.applied,
+            summary.conflict,
+            summary.missing,
+            summary.collision
+        )
+    }
+
+    pub fn stats_summary(&mut self, stats: &Stats, format: SummaryFormat) -> Result<()> {
+        match format {
+            SummaryFormat::Text => {
+                writeln!(
+                    self.output,
+                    "{} processed, {} skipped, {} failed, {} byte(s) transferred in {:.3}s",
+                    stats.processed,
+                    stats.skipped,
+                    stats.failed,
+                    stats.bytes,
+                    stats.elapsed.as_secs_f64()
+                )?;
+                for error in &stats.errors {
+                    writeln!(self.output, "- {}", error)?;
+                }
+                Ok(())
+            }
+            SummaryFormat::Json => {
+                let errors = stats
+                    .errors
+                    .iter()
+                    .map(|error| format!(r#""{}""#, escape_json(error)))
+                    .collect::<Vec<_>>();
+                writeln!(
+                    self.output,
+                    r#"{{"processed":{},"skipped":{},"failed":{},"bytes":{},"elapsed":{:.3},"errors":[{}]}}"#,
+                    stats.processed,
+                    stats.skipped,
+                    stats.failed,
+                    stats.bytes,
+                    stats.elapsed.as_secs_f64(),
+                    errors.join(",")
+                )
+            }
+        }
+    }
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Applicable => "applicable",
+        Status::Applied => "applied",
+        Status::Conflict => "conflict",
+        Status::Missing => "missing",
+        Status::Collision => "collision",
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
+    use std::time::Duration;
+
+    use indoc::indoc;
     use test_case::test_case;
 
     use super::*;
     use crate::testing::{ColoredOuput, OutputChunk};
+    use crate::transfer::fs::Reflink;
 
-    #[test_case(TransferMode::Move, "Moving"  ; "move ")]
-    #[test_case(TransferMode::Copy, "Copying" ; "copy")]
+    #[test_case(TransferMode::Move,                            "Moving"      ; "move ")]
+    #[test_case(TransferMode::Copy { reflink: Reflink::Auto }, "Copying"     ; "copy")]
+    #[test_case(TransferMode::Symlink { relative: false },     "Symlinking"  ; "symlink")]
+    #[test_case(TransferMode::Hardlink,                        "Hardlinking" ; "hardlink")]
     fn begin_transfer(mode: TransferMode, output_action: &str) {
         let mut output = ColoredOuput::new();
 
         TransferLog::new(&mut output)
-            .begin_transfer(mode, &Path::new("a/b.c"), &Path::new("d/e.f"))
+            .begin_transfer(mode, Path::new("a/b.c"), Path::new("d/e.f"))
             .unwrap();
 
         assert_eq!(
@@ -95,6 +215,116 @@ pub mod tests {
         );
     }
 
+    #[test_case(Status::Applicable, Color::Green,   "applicable:"      ; "applicable")]
+    #[test_case(Status::Applied,    Color::Cyan,    "already applied:" ; "applied")]
+    #[test_case(Status::Conflict,   Color::Yellow,  "conflict:"        ; "conflict")]
+    #[test_case(Status::Missing,    Color::Red,     "source missing:"  ; "missing")]
+    #[test_case(Status::Collision,  Color::Magenta, "case collision:"  ; "collision")]
+    fn check_result(status: Status, color: Color, label: &str) {
+        let mut output = ColoredOuput::new();
+
+        TransferLog::new(&mut output)
+            .check_result(status, Path::new("a/b.c"), Path::new("d/e.f"))
+            .unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[
+                OutputChunk::color(color, label),
+                OutputChunk::plain(" '"),
+                OutputChunk::color(Color::Blue, "a/b.c"),
+                OutputChunk::plain("' -> '"),
+                OutputChunk::color(Color::Blue, "d/e.f"),
+                OutputChunk::plain("'\n")
+            ]
+        );
+    }
+
+    #[test]
+    fn check_summary() {
+        let mut output = ColoredOuput::new();
+        let summary = Summary {
+            applicable: 1,
+            applied: 2,
+            conflict: 3,
+            missing: 4,
+            collision: 5,
+        };
+
+        TransferLog::new(&mut output)
+            .check_summary(&summary)
+            .unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[OutputChunk::plain(
+                "1 applicable, 2 already applied, 3 conflict(s), 4 source(s) missing, 5 case collision(s)\n"
+            )]
+        );
+    }
+
+    #[test]
+    fn check_summary_json() {
+        let mut output = ColoredOuput::new();
+        let results = [
+            (Status::Applicable, "a".into(), "b".into()),
+            (Status::Applied, "c".into(), "d".into()),
+            (Status::Conflict, "e\"".into(), "f".into()),
+            (Status::Missing, "g".into(), "h".into()),
+            (Status::Collision, "i".into(), "j".into()),
+        ];
+        let summary = Summary {
+            applicable: 1,
+            applied: 1,
+            conflict: 1,
+            missing: 1,
+            collision: 1,
+        };
+
+        TransferLog::new(&mut output)
+            .check_summary_json(&results, &summary)
+            .unwrap();
+
+        assert_eq!(
+            output.chunks(),
+            &[OutputChunk::plain(concat!(
+                r#"{"results":["#,
+                r#"{"status":"applicable","src":"a","dst":"b"},"#,
+                r#"{"status":"applied","src":"c","dst":"d"},"#,
+                r#"{"status":"conflict","src":"e\"","dst":"f"},"#,
+                r#"{"status":"missing","src":"g","dst":"h"},"#,
+                r#"{"status":"collision","src":"i","dst":"j"}"#,
+                r#"],"applicable":1,"applied":1,"conflict":1,"missing":1,"collision":1}"#,
+                "\n"
+            ))]
+        );
+    }
+
+    #[test_case(SummaryFormat::Text, indoc! {"
+        3 processed, 1 skipped, 1 failed, 1024 byte(s) transferred in 1.500s
+        - Path 'a' not found
+    "} ; "text")]
+    #[test_case(SummaryFormat::Json, indoc! {r#"
+        {"processed":3,"skipped":1,"failed":1,"bytes":1024,"elapsed":1.500,"errors":["Path 'a' not found"]}
+    "#} ; "json")]
+    fn stats_summary(format: SummaryFormat, result: &str) {
+        let mut output = ColoredOuput::new();
+        let stats = Stats {
+            processed: 3,
+            skipped: 1,
+            failed: 1,
+            bytes: 1024,
+            errors: vec!["Path 'a' not found".into()],
+            elapsed: Duration::from_millis(1500),
+        };
+
+        TransferLog::new(&mut output)
+            .stats_summary(&stats, format)
+            .unwrap();
+
+        assert_eq!(output.chunks(), &[OutputChunk::plain(result)]);
+    }
+
     #[test]
     fn end_with_failure() {
         let mut output = ColoredOuput::new();
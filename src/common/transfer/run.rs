@@ -1,14 +1,31 @@
-use crate::input::Terminator;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use same_file::is_same_file;
+
+use termcolor::WriteColor;
+
+use crate::file::make_temp_path;
+use crate::input::{is_stdin_tty, write_stdin_hint, Terminator};
 use crate::output::write_error;
-use crate::run::{Io, Options, Result, EXIT_CODE_IO_ERROR, EXIT_CODE_OK};
+use crate::run::{Io, Options, Result, EXIT_CODE_CHECK_FAILURE, EXIT_CODE_IO_ERROR, EXIT_CODE_OK};
+use crate::transfer::check::{check_transfer, mark_case_collisions, Summary};
 use crate::transfer::fs::{transfer_path, TransferMode};
 use crate::transfer::input::PathDiff;
+use crate::transfer::order::{order_transfers, Transfer};
 use crate::transfer::output::TransferLog;
+use crate::transfer::stats::{transfer_size, Stats, SummaryFormat};
 
 pub trait TransferOptions {
     fn read_nul(&self) -> bool;
     fn verbose(&self) -> bool;
     fn fail_at_end(&self) -> bool;
+    fn reorder(&self) -> bool;
+    fn check(&self) -> bool;
+    fn summary(&self) -> Option<SummaryFormat>;
+    fn unescape(&self) -> bool;
 }
 
 pub fn run_transfer<O>(options: &O, io: &Io, mode: TransferMode) -> Result
@@ -24,36 +41,166 @@ where
         Terminator::Newline { required: false }
     };
 
-    let mut path_diff = PathDiff::new(io.stdin(), terminator);
+    if is_stdin_tty() {
+        write_stdin_hint(
+            &mut io.stderr(),
+            "Reading paths from standard input in diff format (see `rew --diff`).",
+        )?;
+    }
+
+    let mut path_diff = PathDiff::new(io.stdin(), terminator, options.unescape());
     let mut log = TransferLog::new(io.stdout());
+    let mut stats = Stats::default();
     let mut exit_code = EXIT_CODE_OK;
+    let started = Instant::now();
+
+    if options.check() {
+        let mut summary = Summary::default();
+        let mut results = Vec::new();
 
-    while let Some((src_path, dst_path)) = path_diff.read()? {
-        if options.verbose() {
-            log.begin_transfer(mode, &src_path, &dst_path)?;
+        while let Some((src_path, dst_path)) = path_diff.read()? {
+            let status = check_transfer(&src_path, &dst_path, mode);
+            results.push((status, src_path, dst_path));
         }
 
-        match transfer_path(&src_path, &dst_path, mode) {
-            Ok(()) => {
-                if options.verbose() {
-                    log.end_with_success()?;
-                }
+        // Collisions are only known once all destination paths were read
+        mark_case_collisions(&mut results);
+
+        for (status, _, _) in &results {
+            if !status.is_ok() {
+                exit_code = EXIT_CODE_CHECK_FAILURE;
             }
-            Err(error) => {
-                if options.verbose() {
-                    log.end_with_failure()?;
-                }
+            summary.add(*status);
+        }
+
+        if options.summary() == Some(SummaryFormat::Json) {
+            log.check_summary_json(&results, &summary)?;
+        } else {
+            for (status, src_path, dst_path) in &results {
+                log.check_result(*status, src_path, dst_path)?;
+            }
+            log.check_summary(&summary)?;
+        }
+    } else if options.reorder() {
+        let mut paths = Vec::new();
+
+        while let Some(item) = path_diff.read()? {
+            paths.push(item);
+        }
 
-                write_error(&mut io.stderr(), &error)?;
+        let mut temp_paths = Vec::new();
+        let transfers = order_transfers(paths, mode, |path| {
+            let temp_path = make_temp_path(path);
+            temp_paths.push((path.to_path_buf(), temp_path.clone()));
+            temp_path
+        });
 
-                if options.fail_at_end() {
+        for transfer in transfers {
+            if !run_single_transfer(options, io, &mut log, &mut stats, &transfer)? {
+                exit_code = EXIT_CODE_IO_ERROR;
+                if !options.fail_at_end() {
+                    break;
+                }
+            }
+        }
+
+        // Source paths moved aside to resolve cycles, whose transfer failed or did not happen.
+        for (path, temp_path) in temp_paths {
+            if temp_path.symlink_metadata().is_ok() {
+                if let Err(error) = restore_temp_path(&path, &temp_path) {
+                    write_error(&mut io.stderr(), &error)?;
                     exit_code = EXIT_CODE_IO_ERROR;
-                } else {
-                    return Ok(EXIT_CODE_IO_ERROR);
                 }
             }
         }
+    } else {
+        while let Some((src_path, dst_path)) = path_diff.read()? {
+            let transfer = Transfer {
+                src_path,
+                dst_path,
+                mode,
+            };
+
+            if !run_single_transfer(options, io, &mut log, &mut stats, &transfer)? {
+                exit_code = EXIT_CODE_IO_ERROR;
+                if !options.fail_at_end() {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Check mode prints its own summary
+    if let (Some(format), false) = (options.summary(), options.check()) {
+        stats.elapsed = started.elapsed();
+        log.stats_summary(&stats, format)?;
     }
 
     Ok(exit_code)
 }
+
+fn restore_temp_path(path: &Path, temp_path: &Path) -> io::Result<()> {
+    if path.symlink_metadata().is_ok() {
+        return Err(io::Error::other(format!(
+            "Path '{}' was left at temporary path '{}', because the original path is occupied",
+            path.to_string_lossy(),
+            temp_path.to_string_lossy()
+        )));
+    }
+    fs::rename(temp_path, path).map_err(|error| {
+        io::Error::other(format!(
+            "Path '{}' was left at temporary path '{}': {}",
+            path.to_string_lossy(),
+            temp_path.to_string_lossy(),
+            error
+        ))
+    })
+}
+
+fn run_single_transfer<O, L>(
+    options: &O,
+    io: &Io,
+    log: &mut TransferLog<L>,
+    stats: &mut Stats,
+    transfer: &Transfer,
+) -> io::Result<bool>
+where
+    O: TransferOptions,
+    L: Write + WriteColor,
+{
+    if options.verbose() {
+        log.begin_transfer(transfer.mode, &transfer.src_path, &transfer.dst_path)?;
+    }
+
+    // Measure before the transfer, source path might be gone afterwards.
+    let (skipped, bytes) = if options.summary().is_some() {
+        (
+            is_same_file(&transfer.src_path, &transfer.dst_path).unwrap_or(false),
+            transfer_size(&transfer.src_path, transfer.mode),
+        )
+    } else {
+        (false, 0)
+    };
+
+    match transfer_path(&transfer.src_path, &transfer.dst_path, transfer.mode) {
+        Ok(()) => {
+            if options.verbose() {
+                log.end_with_success()?;
+            }
+            if skipped {
+                stats.add_skip();
+            } else {
+                stats.add_success(bytes);
+            }
+            Ok(true)
+        }
+        Err(error) => {
+            if options.verbose() {
+                log.end_with_failure()?;
+            }
+            stats.add_failure(error.to_string());
+            write_error(&mut io.stderr(), &error)?;
+            Ok(false)
+        }
+    }
+}
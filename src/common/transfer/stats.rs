@@ -0,0 +1,149 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::transfer::fs::TransferMode;
+
+pub const SUMMARY_CHOICES: &[&str] = &[TEXT, JSON];
+
+const TEXT: &str = "text";
+const JSON: &str = "json";
+
+const MAX_ERROR_SAMPLES: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SummaryFormat {
+    Text,
+    Json,
+}
+
+pub fn parse_summary_format(string: &str) -> Result<SummaryFormat, &'static str> {
+    match string {
+        TEXT => Ok(SummaryFormat::Text),
+        JSON => Ok(SummaryFormat::Json),
+        _ => Err("invalid value"),
+    }
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct Stats {
+    pub processed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub bytes: u64,
+    pub errors: Vec<String>,
+    pub elapsed: Duration,
+}
+
+impl Stats {
+    pub fn add_success(&mut self, bytes: u64) {
+        self.processed += 1;
+        self.bytes += bytes;
+    }
+
+    pub fn add_skip(&mut self) {
+        self.processed += 1;
+        self.skipped += 1;
+    }
+
+    pub fn add_failure(&mut self, error: String) {
+        self.processed += 1;
+        self.failed += 1;
+
+        if self.errors.len() < MAX_ERROR_SAMPLES {
+            self.errors.push(error);
+        }
+    }
+}
+
+pub fn transfer_size(src_path: &Path, mode: TransferMode) -> u64 {
+    match mode {
+        TransferMode::Move | TransferMode::Copy { .. } => {
+            fs_extra::dir::get_size(src_path).unwrap_or(0)
+        }
+        TransferMode::Symlink { .. } | TransferMode::Hardlink => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use assert_fs::TempDir;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::transfer::fs::Reflink;
+
+    #[test_case("text", Ok(SummaryFormat::Text) ; "text")]
+    #[test_case("json", Ok(SummaryFormat::Json) ; "json")]
+    #[test_case("xml",  Err("invalid value")    ; "invalid")]
+    fn parse_summary_format(string: &str, result: Result<SummaryFormat, &'static str>) {
+        assert_eq!(super::parse_summary_format(string), result);
+    }
+
+    #[test]
+    fn add() {
+        let mut stats = Stats::default();
+        stats.add_success(10);
+        stats.add_success(20);
+        stats.add_skip();
+
+        for index in 0..5 {
+            stats.add_failure(format!("error {}", index));
+        }
+
+        assert_eq!(
+            stats,
+            Stats {
+                processed: 8,
+                skipped: 1,
+                failed: 5,
+                bytes: 30,
+                errors: vec!["error 0".into(), "error 1".into(), "error 2".into()],
+                elapsed: Duration::default(),
+            }
+        );
+    }
+
+    #[test_case(TransferMode::Move,                            3 ; "move ")]
+    #[test_case(TransferMode::Copy { reflink: Reflink::Auto }, 3 ; "copy")]
+    #[test_case(TransferMode::Symlink { relative: false },     0 ; "symlink")]
+    #[test_case(TransferMode::Hardlink,                        0 ; "hardlink")]
+    fn transfer_size(mode: TransferMode, result: u64) {
+        let dir = TempDir::new().unwrap();
+        let file = dir.child("file");
+        file.write_str("abc").unwrap();
+
+        assert_eq!(super::transfer_size(file.path(), mode), result);
+    }
+
+    #[test]
+    fn transfer_size_dir() {
+        let dir = TempDir::new().unwrap();
+        dir.child("a").write_str("abc").unwrap();
+        dir.child("b/c").write_str("de").unwrap();
+
+        assert_eq!(
+            super::transfer_size(
+                dir.path(),
+                TransferMode::Copy {
+                    reflink: Reflink::Auto
+                }
+            ),
+            5
+        );
+    }
+
+    #[test]
+    fn transfer_size_missing() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(
+            super::transfer_size(
+                &dir.path().join("missing"),
+                TransferMode::Copy {
+                    reflink: Reflink::Auto
+                }
+            ),
+            0
+        );
+    }
+}
@@ -1,39 +1,114 @@
 use std::io::{Error, ErrorKind, Result};
-
-static mut STATIC_STRINGS: Vec<String> = Vec::new();
+use std::str::Utf8Error;
 
 pub fn into_static_str(value: String) -> &'static str {
-    unsafe {
-        // Well, this is ugly but the current usage should be actually safe:
-        // 1) It's used only by cli.rs to generate static strings for clap attributes.
-        // 2) Values are never modified after being pushed to vector.
-        // 3) Vectors is only modified / accessed by a single thread.
-        STATIC_STRINGS.push(value);
-        STATIC_STRINGS
-            .last()
-            .expect("Expected at least one static string result")
-            .as_str()
-    }
+    // This is only used by cli.rs to generate static strings for clap attributes.
+    // Their number is small and fixed, so leaking them is fine.
+    Box::leak(value.into_boxed_str())
 }
 
 pub fn str_from_utf8(data: &[u8]) -> Result<&str> {
-    match std::str::from_utf8(data) {
-        Ok(str) => Ok(str),
-        Err(error) => Err(Error::new(
-            ErrorKind::InvalidData,
-            format!(
-                "Value does not have UTF-8 encoding (offset {})",
-                error.valid_up_to()
-            ),
-        )),
+    std::str::from_utf8(data).map_err(make_utf8_error)
+}
+
+pub fn make_utf8_error(error: Utf8Error) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Value does not have UTF-8 encoding (offset {})",
+            error.valid_up_to()
+        ),
+    )
+}
+
+// Backslash is escaped too, so the result can be decoded back by `unescape_bytes`.
+pub fn escape_bytes(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len());
+
+    for chunk in data.utf8_chunks() {
+        for char in chunk.valid().chars() {
+            if char == '\\' {
+                result.push_str("\\\\");
+            } else {
+                result.push(char);
+            }
+        }
+        for byte in chunk.invalid() {
+            result.push_str(&format!("\\x{:02X}", byte));
+        }
     }
+
+    result
+}
+
+pub fn unescape_bytes(value: &str) -> Result<Vec<u8>> {
+    let mut result = Vec::with_capacity(value.len());
+    let mut bytes = value.bytes();
+
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            result.push(byte);
+            continue;
+        }
+
+        match bytes.next() {
+            Some(b'\\') => result.push(b'\\'),
+            Some(b'x') => {
+                let digits = [bytes.next(), bytes.next()];
+                let byte = match digits {
+                    [Some(high), Some(low)] => std::str::from_utf8(&[high, low])
+                        .ok()
+                        .and_then(|digits| u8::from_str_radix(digits, 16).ok()),
+                    _ => None,
+                };
+                match byte {
+                    Some(byte) => result.push(byte),
+                    None => return Err(make_escape_error(value)),
+                }
+            }
+            _ => return Err(make_escape_error(value)),
+        }
+    }
+
+    Ok(result)
+}
+
+fn make_escape_error(value: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Value '{}' contains invalid escape sequence (expected '\\\\' or '\\xNN')",
+            value
+        ),
+    )
+}
+
+pub fn escape_json(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for char in value.chars() {
+        match char {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            char if char.is_control() => result.push_str(&format!("\\u{:04x}", char as u32)),
+            char => result.push(char),
+        }
+    }
+
+    result
 }
 
 #[cfg(test)]
 mod tests {
+    use test_case::test_case;
+
     use super::*;
+    use crate::testing::unpack_io_error;
 
-    #[test] // Do not use test_case, this is expected to work only for a single thread.
+    #[test]
     fn into_static_str() {
         let str_1 = super::into_static_str("a".into());
         let str_2 = super::into_static_str("ab".into());
@@ -44,6 +119,52 @@ mod tests {
         assert_eq!(str_3, "abc");
     }
 
+    #[test_case(b"abc",          "abc"            ; "valid")]
+    #[test_case(b"a\\b",         "a\\\\b"         ; "backslash")]
+    #[test_case(b"a\xFFb",       "a\\xFFb"        ; "invalid byte")]
+    #[test_case(b"\xF0\x9F\x92", "\\xF0\\x9F\\x92" ; "incomplete sequence")]
+    #[test_case(b"\xC3\xA1\xC3", "á\\xC3"         ; "incomplete end")]
+    fn escape_bytes(data: &[u8], result: &str) {
+        assert_eq!(super::escape_bytes(data), result);
+    }
+
+    #[test_case("abc",             b"abc"            ; "plain")]
+    #[test_case("a\\\\b",          b"a\\b"          ; "backslash")]
+    #[test_case("a\\xFFb",         b"a\xFFb"        ; "byte")]
+    #[test_case("\\xf0\\x9F\\x92", b"\xF0\x9F\x92" ; "lowercase hex")]
+    fn unescape_bytes(value: &str, result: &[u8]) {
+        assert_eq!(
+            super::unescape_bytes(value).map_err(unpack_io_error),
+            Ok(result.to_vec())
+        );
+    }
+
+    #[test_case("a\\b"   ; "unknown sequence")]
+    #[test_case("a\\"    ; "trailing backslash")]
+    #[test_case("a\\xF"  ; "missing digit")]
+    #[test_case("a\\xGG" ; "invalid digit")]
+    fn unescape_bytes_err(value: &str) {
+        assert_eq!(
+            super::unescape_bytes(value).map_err(unpack_io_error),
+            Err((
+                ErrorKind::InvalidData,
+                format!(
+                    "Value '{}' contains invalid escape sequence (expected '\\\\' or '\\xNN')",
+                    value
+                )
+            ))
+        );
+    }
+
+    #[test_case("abc",     "abc"         ; "plain")]
+    #[test_case("a\"b",    "a\\\"b"      ; "quote")]
+    #[test_case("a\\b",    "a\\\\b"      ; "backslash")]
+    #[test_case("a\nb\tc", "a\\nb\\tc"   ; "whitespace")]
+    #[test_case("a\0b",    "a\\u0000b"   ; "control")]
+    fn escape_json(value: &str, result: &str) {
+        assert_eq!(super::escape_json(value), result);
+    }
+
     mod str_from_utf8 {
         use super::*;
         use crate::testing::unpack_io_error;
@@ -110,6 +110,97 @@ mod failure {
     }
 }
 
+mod reorder {
+    use super::*;
+
+    #[test]
+    fn chain() {
+        let dir = temp_dir();
+
+        let src_file_1 = write(dir.child("a"), "1");
+        let src_file_2 = write(dir.child("b"), "2");
+        let dst_file = dir.child("c");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--reorder")
+            .write_stdin("<a\n>b\n<b\n>c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file_1.assert("1");
+        src_file_2.assert("1");
+        dst_file.assert("2");
+    }
+
+    #[test]
+    fn cycle() {
+        let dir = temp_dir();
+
+        let file_1 = write(dir.child("a"), "1");
+        let file_2 = write(dir.child("b"), "2");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--reorder")
+            .write_stdin("<a\n>b\n<b\n>a")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        file_1.assert("2");
+        file_2.assert("1");
+        assert_eq!(dir.read_dir().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn multiple_readers() {
+        let dir = temp_dir();
+
+        let file_1 = write(dir.child("a"), "1");
+        let file_2 = dir.child("b");
+        let file_3 = write(dir.child("c"), "3");
+        let file_4 = dir.child("d");
+
+        cpb()
+            .current_dir(dir.path())
+            .arg("--reorder")
+            .write_stdin("<c\n>a\n<a\n>b\n<a\n>d")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        file_1.assert("3");
+        file_2.assert("1");
+        file_3.assert("3");
+        file_4.assert("1");
+    }
+}
+
+#[test]
+fn reflink_never() {
+    let dir = temp_dir();
+
+    let src_file = write(dir.child("a"), "1");
+    let dst_file = write(dir.child("b"), "2");
+
+    cpb()
+        .current_dir(dir.path())
+        .arg("--reflink=never")
+        .write_stdin("<a\n>b")
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    src_file.assert("1");
+    dst_file.assert("1");
+}
+
 mod verbose {
     use super::*;
 
@@ -205,3 +296,13 @@ fn help() {
         .stdout(predicate::str::is_empty().not())
         .stderr("");
 }
+
+#[test]
+fn help_exit_codes() {
+    cpb()
+        .arg("--help-exit-codes")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().not())
+        .stderr("");
+}
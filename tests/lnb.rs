@@ -0,0 +1,360 @@
+#[path = "utils.rs"]
+mod utils;
+
+use std::fs;
+use std::path::Path;
+
+use assert_fs::prelude::*;
+use indoc::indoc;
+use predicates::prelude::*;
+use utils::{lnb, temp_dir, write};
+
+#[test]
+fn no_input() {
+    lnb().assert().success();
+}
+
+mod input_terminator {
+    use super::*;
+
+    #[test]
+    fn line() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b");
+
+        lnb()
+            .current_dir(dir.path())
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("1");
+    }
+
+    #[test]
+    fn null() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = dir.child("b");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--read-nul")
+            .write_stdin("<a\0>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("1");
+    }
+}
+
+mod mode {
+    use super::*;
+
+    #[test]
+    fn symlink() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+        let dst_file = dir.child("b/c");
+
+        lnb()
+            .current_dir(dir.path())
+            .write_stdin("<a\n>b/c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dst_file.assert("1");
+        assert!(fs::read_link(dst_file.path()).unwrap().is_absolute());
+    }
+
+    #[test]
+    fn symlink_relative() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+        let dst_file = dir.child("b/c");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--relative")
+            .write_stdin("<a\n>b/c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dst_file.assert("1");
+        assert_eq!(
+            fs::read_link(dst_file.path()).unwrap(),
+            Path::new("..").join("a")
+        );
+    }
+
+    #[test]
+    fn hardlink() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("b"), "2");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--mode=hardlink")
+            .write_stdin("<a\n>b")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("1");
+        assert!(fs::read_link(dst_file.path()).is_err());
+    }
+
+    #[test]
+    fn hardlink_dir() {
+        let dir = temp_dir();
+
+        dir.child("a").create_dir_all().unwrap();
+        let dst_dir = dir.child("b");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--mode=hardlink")
+            .write_stdin("<a\n>b")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Cannot create hard link to directory 'a'\n");
+
+        dst_dir.assert(predicates::path::missing());
+    }
+}
+
+mod replace {
+    use super::*;
+
+    #[test]
+    fn dir_link_with_dir() {
+        let dir = temp_dir();
+
+        write(dir.child("d1/a"), "1");
+        write(dir.child("d2/b"), "2");
+        let dst_link = dir.child("l");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--relative")
+            .write_stdin("<d1\n>l")
+            .assert()
+            .success();
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--relative")
+            .write_stdin("<d2\n>l")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dir.child("d1/a").assert("1");
+        dst_link.child("b").assert("2");
+        assert_eq!(fs::read_link(dst_link.path()).unwrap(), Path::new("d2"));
+    }
+
+    #[test]
+    fn dir_link_with_file() {
+        let dir = temp_dir();
+
+        write(dir.child("d1/a"), "1");
+        write(dir.child("f"), "2");
+        let dst_link = dir.child("l");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--relative")
+            .write_stdin("<d1\n>l")
+            .assert()
+            .success();
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--relative")
+            .write_stdin("<f\n>l")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dir.child("d1/a").assert("1");
+        dst_link.assert("2");
+        assert_eq!(fs::read_link(dst_link.path()).unwrap(), Path::new("f"));
+    }
+
+    #[test]
+    fn file_with_dir() {
+        let dir = temp_dir();
+
+        write(dir.child("d2/b"), "2");
+        let dst_file = write(dir.child("g"), "1");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--relative")
+            .write_stdin("<d2\n>g")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        dst_file.child("b").assert("2");
+        assert_eq!(fs::read_link(dst_file.path()).unwrap(), Path::new("d2"));
+    }
+
+    #[test]
+    fn dir_with_file() {
+        let dir = temp_dir();
+
+        write(dir.child("f"), "1");
+        let dst_dir = dir.child("d");
+        dst_dir.create_dir_all().unwrap();
+
+        lnb()
+            .current_dir(dir.path())
+            .write_stdin("<f\n>d")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Cannot overwrite directory 'd' with link to 'f'\n");
+
+        dst_dir.assert(predicates::path::is_dir());
+    }
+}
+
+#[test]
+fn check() {
+    let dir = temp_dir();
+
+    write(dir.child("a"), "1");
+    write(dir.child("b"), "2");
+    dir.child("c").create_dir_all().unwrap();
+
+    lnb()
+        .current_dir(dir.path())
+        .arg("--check")
+        .write_stdin("<a\n>b\n<a\n>c")
+        .assert()
+        .failure()
+        .code(5)
+        .stdout(indoc! {"
+            applicable: 'a' -> 'b'
+            conflict: 'a' -> 'c'
+            1 applicable, 0 already applied, 1 conflict(s), 0 source(s) missing, 0 case collision(s)
+        "})
+        .stderr("");
+
+    dir.child("b").assert("2");
+}
+
+mod failure {
+    use super::*;
+
+    #[test]
+    fn immediate() {
+        let dir = temp_dir();
+
+        write(dir.child("a2"), "2");
+
+        let dst_file_1 = dir.child("b1");
+        let dst_file_2 = dir.child("b2");
+
+        lnb()
+            .current_dir(dir.path())
+            .write_stdin("<a1\n>b1\n<a2\n>b2")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'a1' not found or user lacks permission\n");
+
+        dst_file_1.assert(predicates::path::missing());
+        dst_file_2.assert(predicates::path::missing());
+    }
+
+    #[test]
+    fn at_end() {
+        let dir = temp_dir();
+
+        write(dir.child("a2"), "2");
+
+        let dst_file_1 = dir.child("b1");
+        let dst_file_2 = dir.child("b2");
+
+        lnb()
+            .current_dir(dir.path())
+            .arg("--fail-at-end")
+            .write_stdin("<a1\n>b1\n<a2\n>b2")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'a1' not found or user lacks permission\n");
+
+        dst_file_1.assert(predicates::path::missing());
+        dst_file_2.assert("2");
+    }
+}
+
+#[test]
+fn verbose() {
+    let dir = temp_dir();
+
+    write(dir.child("a"), "1");
+    let dst_file = dir.child("b");
+
+    lnb()
+        .current_dir(dir.path())
+        .arg("--verbose")
+        .write_stdin("<a\n>b")
+        .assert()
+        .success()
+        .stdout("Symlinking 'a' to 'b' ... OK\n")
+        .stderr("");
+
+    dst_file.assert("1");
+}
+
+#[test]
+fn help() {
+    lnb()
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().not())
+        .stderr("");
+}
+
+#[test]
+fn help_exit_codes() {
+    lnb()
+        .arg("--help-exit-codes")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().not())
+        .stderr("");
+}
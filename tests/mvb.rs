@@ -2,6 +2,7 @@
 mod utils;
 
 use assert_fs::prelude::*;
+use indoc::indoc;
 use predicates::prelude::*;
 use utils::{mvb, temp_dir, write};
 
@@ -110,6 +111,251 @@ mod failure {
     }
 }
 
+mod reorder {
+    use super::*;
+
+    #[test]
+    fn chain() {
+        let dir = temp_dir();
+
+        let src_file_1 = write(dir.child("a"), "1");
+        let src_file_2 = write(dir.child("b"), "2");
+        let dst_file = dir.child("c");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--reorder")
+            .write_stdin("<a\n>b\n<b\n>c")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        src_file_1.assert(predicates::path::missing());
+        src_file_2.assert("1");
+        dst_file.assert("2");
+    }
+
+    #[test]
+    fn cycle() {
+        let dir = temp_dir();
+
+        let file_1 = write(dir.child("a"), "1");
+        let file_2 = write(dir.child("b"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--reorder")
+            .write_stdin("<a\n>b\n<b\n>a")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        file_1.assert("2");
+        file_2.assert("1");
+        assert_eq!(dir.read_dir().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn cycle_failure() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--reorder")
+            .write_stdin("<a\n>b\n<b\n>a")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Path 'b' not found or user lacks permission\n");
+
+        dir.child("a").assert("1"); // Moved back from temporary path
+        dir.child("b").assert(predicate::path::missing());
+        assert_eq!(dir.read_dir().unwrap().count(), 1);
+    }
+}
+
+mod check {
+    use super::*;
+
+    #[test]
+    fn ok() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_file = write(dir.child("c"), "2");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a\n>b\n<x\n>c")
+            .assert()
+            .success()
+            .stdout(indoc! {"
+                applicable: 'a' -> 'b'
+                already applied: 'x' -> 'c'
+                1 applicable, 1 already applied, 0 conflict(s), 0 source(s) missing, 0 case collision(s)
+            "})
+            .stderr("");
+
+        src_file.assert("1");
+        dst_file.assert("2");
+    }
+
+    #[test]
+    fn err() {
+        let dir = temp_dir();
+
+        let src_file = write(dir.child("a"), "1");
+        let dst_dir = dir.child("b");
+        dst_dir.create_dir_all().unwrap();
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a\n>b\n<x\n>y")
+            .assert()
+            .failure()
+            .code(5)
+            .stdout(indoc! {"
+                conflict: 'a' -> 'b'
+                source missing: 'x' -> 'y'
+                0 applicable, 0 already applied, 1 conflict(s), 1 source(s) missing, 0 case collision(s)
+            "})
+            .stderr("");
+
+        src_file.assert("1");
+        dst_dir.assert(predicates::path::is_dir());
+    }
+
+    #[test]
+    fn json() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .arg("--summary=json")
+            .write_stdin("<a\n>b\n<x\n>y")
+            .assert()
+            .failure()
+            .code(5)
+            .stdout(concat!(
+                r#"{"results":[{"status":"applicable","src":"a","dst":"b"},{"status":"missing","src":"x","dst":"y"}],"#,
+                r#""applicable":1,"applied":0,"conflict":0,"missing":1,"collision":0}"#,
+                "\n"
+            ))
+            .stderr("");
+
+        dir.child("a").assert("1");
+    }
+
+    #[test]
+    fn case_collision() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "1");
+        write(dir.child("b"), "2");
+        write(dir.child("c"), "3");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--check")
+            .write_stdin("<a\n>x.txt\n<b\n>y.txt\n<c\n>X.TXT")
+            .assert()
+            .failure()
+            .code(5)
+            .stdout(indoc! {"
+                case collision: 'a' -> 'x.txt'
+                applicable: 'b' -> 'y.txt'
+                case collision: 'c' -> 'X.TXT'
+                1 applicable, 0 already applied, 0 conflict(s), 0 source(s) missing, 2 case collision(s)
+            "})
+            .stderr("");
+
+        dir.child("a").assert("1");
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn unescape() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = temp_dir();
+    let src_file = write(dir.child(OsStr::from_bytes(b"a\xFF")), "1");
+    let dst_file = dir.child(OsStr::from_bytes(b"b\\\xFF"));
+
+    mvb()
+        .current_dir(dir.path())
+        .arg("--unescape")
+        .write_stdin("<a\\xFF\n>b\\\\\\xFF")
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    src_file.assert(predicate::path::missing());
+    dst_file.assert("1");
+}
+
+mod summary {
+    use super::*;
+
+    #[test]
+    fn text() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "abc");
+        write(dir.child("c"), "d");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--summary")
+            .write_stdin("<a\n>b\n<c\n>c")
+            .assert()
+            .success()
+            .stdout(
+                predicate::str::is_match(
+                    r"^2 processed, 1 skipped, 0 failed, 3 byte\(s\) transferred in \d+\.\d{3}s\n$",
+                )
+                .unwrap(),
+            )
+            .stderr("");
+
+        dir.child("b").assert("abc");
+    }
+
+    #[test]
+    fn json() {
+        let dir = temp_dir();
+
+        write(dir.child("a"), "abc");
+
+        mvb()
+            .current_dir(dir.path())
+            .arg("--summary=json")
+            .arg("--fail-at-end")
+            .write_stdin("<x\n>y\n<a\n>b")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout(predicate::str::is_match(
+                r#"^\{"processed":2,"skipped":0,"failed":1,"bytes":3,"elapsed":\d+\.\d{3},"errors":\["Path 'x' not found or user lacks permission"\]\}\n$"#
+            ).unwrap())
+            .stderr("error: Path 'x' not found or user lacks permission\n");
+
+        dir.child("b").assert("abc");
+    }
+}
+
 mod verbose {
     use super::*;
 
@@ -202,3 +448,13 @@ fn help() {
         .stdout(predicate::str::is_empty().not())
         .stderr("");
 }
+
+#[test]
+fn help_exit_codes() {
+    mvb()
+        .arg("--help-exit-codes")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().not())
+        .stderr("");
+}
@@ -4,9 +4,10 @@ mod utils;
 use std::env;
 use std::path::Path;
 
+use assert_fs::prelude::*;
 use indoc::indoc;
 use predicates::prelude::*;
-use utils::rew;
+use utils::{rew, temp_dir, write};
 
 mod no_pattern {
     use super::*;
@@ -148,6 +149,35 @@ mod input_terminator {
     }
 }
 
+mod invalid_utf8 {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("lossy",  "a\u{FFFD}b\nc\n" ; "lossy")]
+    #[test_case("escape", "a\\xFFb\nc\n"   ; "escape")]
+    #[test_case("skip",   "c\n"            ; "skip")]
+    fn ok(policy: &str, output: &str) {
+        rew()
+            .arg(format!("--invalid-utf8={}", policy))
+            .write_stdin(&b"a\xFFb\nc"[..])
+            .assert()
+            .success()
+            .stdout(output.to_string())
+            .stderr("");
+    }
+
+    #[test]
+    fn err() {
+        rew()
+            .write_stdin(&b"a\xFFb\nc"[..])
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("")
+            .stderr("error: Value does not have UTF-8 encoding (offset 1)\n");
+    }
+}
+
 mod output_terminator {
     use super::*;
 
@@ -201,6 +231,118 @@ mod output_terminator {
     }
 }
 
+mod strict_terminator {
+    use super::*;
+
+    #[test]
+    fn ok() {
+        rew()
+            .arg("--strict-terminator")
+            .arg("--read-nul")
+            .arg("--diff")
+            .arg("_{}_")
+            .write_stdin("a\0b")
+            .assert()
+            .success()
+            .stdout("<a\n>_a_\n<b\n>_b_\n")
+            .stderr("");
+    }
+
+    #[test]
+    fn err() {
+        rew()
+            .arg("--strict-terminator")
+            .arg("--read-nul")
+            .arg("--diff")
+            .arg("_{}_")
+            .write_stdin("a\0b\nc")
+            .assert()
+            .failure()
+            .code(1)
+            .stdout("<a\n>_a_\n")
+            .stderr("error: Value 'b\\nc' contains output terminator '\\n'\n");
+    }
+}
+
+mod output_file {
+    use super::*;
+
+    #[test]
+    fn replace() {
+        let dir = temp_dir();
+        let file = write(dir.child("file"), "old\n");
+
+        rew()
+            .arg("--output")
+            .arg(file.path())
+            .arg("_{}_")
+            .write_stdin("a\nb")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        file.assert("_a_\n_b_\n");
+    }
+
+    #[test]
+    fn append() {
+        let dir = temp_dir();
+        let file = write(dir.child("file"), "old\n");
+
+        rew()
+            .arg("--output")
+            .arg(file.path())
+            .arg("--append")
+            .arg("_{}_")
+            .write_stdin("a\nb")
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        file.assert("old\n_a_\n_b_\n");
+    }
+
+    #[test]
+    fn same_as_input() {
+        let dir = temp_dir();
+        let file = write(dir.child("file"), "a\nb\n");
+
+        rew()
+            .arg("--output")
+            .arg(file.path())
+            .arg("_{}_")
+            .pipe_stdin(file.path())
+            .unwrap()
+            .assert()
+            .success()
+            .stdout("")
+            .stderr("");
+
+        file.assert("_a_\n_b_\n");
+    }
+
+    #[test]
+    fn failure() {
+        let dir = temp_dir();
+        let file = write(dir.child("file"), "old\n");
+
+        rew()
+            .arg("--output")
+            .arg(file.path())
+            .arg("{P}")
+            .write_stdin("non-existent")
+            .assert()
+            .failure()
+            .code(4)
+            .stdout("");
+
+        file.assert("old\n");
+        assert_eq!(dir.read_dir().unwrap().count(), 1);
+    }
+}
+
 mod output_mode {
     use super::*;
 
@@ -235,6 +377,51 @@ mod output_mode {
             "})
             .stderr("");
     }
+
+    #[test]
+    fn script() {
+        rew()
+            .arg("--script")
+            .arg("{s:a:b}")
+            .write_stdin("a'1\nc")
+            .assert()
+            .success()
+            .stdout(indoc! {"
+                #!/bin/sh
+                set -e
+                mv -- 'a'\\''1' 'b'\\''1'
+            "})
+            .stderr("");
+    }
+
+    #[test]
+    fn script_parent_dir() {
+        rew()
+            .arg("--script")
+            .arg("all/{f}")
+            .write_stdin("a/b")
+            .assert()
+            .success()
+            .stdout(indoc! {r#"
+                #!/bin/sh
+                set -e
+                mkdir -p -- "$(dirname -- 'all/b')"
+                mv -- 'a/b' 'all/b'
+            "#})
+            .stderr("");
+    }
+
+    #[test]
+    fn script_empty() {
+        rew()
+            .arg("--script=pwsh")
+            .arg("{}")
+            .write_stdin("")
+            .assert()
+            .success()
+            .stdout("$ErrorActionPreference = 'Stop'\n")
+            .stderr("");
+    }
 }
 
 mod counter {
@@ -311,6 +498,34 @@ mod regex {
             .stdout("2\n")
             .stderr("");
     }
+
+    #[test]
+    fn too_big() {
+        rew()
+            .arg("--regex=(\\w{100}){100}")
+            .write_stdin("a")
+            .assert()
+            .failure()
+            .code(2)
+            .stdout("")
+            .stderr(predicate::str::contains(
+                "Invalid value for '--regex <regex>': Compiled regex exceeds size limit",
+            ));
+    }
+
+    #[test]
+    fn too_big_in_pattern() {
+        rew()
+            .arg("{=1:(\\w%{100%})%{100%}}")
+            .write_stdin("a")
+            .assert()
+            .failure()
+            .code(3)
+            .stdout("")
+            .stderr(predicate::str::contains(
+                "Invalid regular expression 'Compiled regex exceeds size limit",
+            ));
+    }
 }
 
 mod errors {
@@ -388,6 +603,48 @@ mod errors {
     }
 }
 
+mod seed {
+    use super::*;
+
+    fn output(seed_arg: bool) -> Vec<u8> {
+        let mut command = rew();
+        if seed_arg {
+            command.arg("--seed=123");
+        } else {
+            command.env("REW_SEED", "123");
+        }
+        command
+            .arg("{u}:{U}")
+            .write_stdin("a\nb")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone()
+    }
+
+    #[test]
+    fn arg() {
+        assert_eq!(output(true), output(true));
+    }
+
+    #[test]
+    fn env() {
+        assert_eq!(output(false), output(true));
+    }
+
+    #[test]
+    fn stable() {
+        assert_eq!(
+            String::from_utf8(output(true)).unwrap(),
+            indoc! {"
+                15743780927533230995:01a1bb22-4e9f-4f99-a26a-730e2ba7845c
+                353760294419238612:1a8bdbfd-b830-44eb-a894-4fb1d4dd10c3
+            "}
+        );
+    }
+}
+
 mod working_dir {
     use super::*;
 
@@ -543,6 +800,16 @@ mod help {
             .stdout(predicate::str::is_empty().not())
             .stderr("");
     }
+
+    #[test]
+    fn exit_codes() {
+        rew()
+            .arg("--help-exit-codes")
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty().not())
+            .stderr("");
+    }
 }
 
 #[test]
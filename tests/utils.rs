@@ -17,6 +17,11 @@ pub fn mvb() -> Command {
     command("mvb")
 }
 
+#[allow(dead_code)]
+pub fn lnb() -> Command {
+    command("lnb")
+}
+
 pub fn command(name: &str) -> Command {
     Command::cargo_bin(name).unwrap()
 }